@@ -0,0 +1,379 @@
+//! Fluent builders for the objects that are tedious to assemble by hand.
+
+use crate::{
+    Header, Link, Map, MediaType, Operation, Parameter, Referenceable, RequestBody, Response,
+    Schema,
+};
+use std::fmt;
+
+/// Why a builder could not produce a valid object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    /// an operation must declare at least one response, by status code or `default`
+    NoResponses,
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::NoResponses => write!(f, "an operation must declare at least one response"),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+/// Builder for [`RequestBody`], created by [`request_body`].
+#[derive(Debug, Clone, Default)]
+pub struct RequestBodyBuilder {
+    description: Option<String>,
+    required: Option<bool>,
+    content: Map<String, MediaType>,
+}
+
+impl RequestBodyBuilder {
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+    pub fn required(mut self, required: bool) -> Self {
+        self.required = Some(required);
+        self
+    }
+    pub fn content(mut self, media_type: &str, content: MediaType) -> Self {
+        self.content.insert(media_type.to_owned(), content);
+        self
+    }
+    /// shorthand for an `application/json` content carrying the given schema
+    pub fn json(self, schema: Referenceable<Schema>) -> Self {
+        self.content(
+            "application/json",
+            MediaType {
+                schema: Some(schema),
+                ..Default::default()
+            },
+        )
+    }
+    pub fn build(self) -> RequestBody {
+        RequestBody {
+            description: self.description,
+            required: self.required,
+            content: self.content,
+            extras: None,
+        }
+    }
+}
+
+/// start building a request body
+///
+/// ```rust
+/// use oas::builders::request_body;
+/// use oas::{MediaType, Referenceable, Schema};
+///
+/// let pet = Referenceable::Data(Schema {
+///     _type: Some("object".to_string()),
+///     ..Default::default()
+/// });
+/// let body = request_body()
+///     .description("the pet to create")
+///     .required(true)
+///     .json(pet.clone())
+///     .content(
+///         "application/x-www-form-urlencoded",
+///         MediaType {
+///             schema: Some(pet),
+///             ..Default::default()
+///         },
+///     )
+///     .build();
+/// assert_eq!(body.content.len(), 2);
+/// assert_eq!(body.required, Some(true));
+/// ```
+pub fn request_body() -> RequestBodyBuilder {
+    RequestBodyBuilder::default()
+}
+
+fn non_empty<K, V>(map: Map<K, V>) -> Option<Map<K, V>> {
+    if map.is_empty() {
+        None
+    } else {
+        Some(map)
+    }
+}
+
+/// Builder for [`Response`], created by [`response`].
+#[derive(Debug, Clone)]
+pub struct ResponseBuilder {
+    description: String,
+    headers: Map<String, Referenceable<Header>>,
+    content: Map<String, MediaType>,
+    links: Map<String, Referenceable<Link>>,
+}
+
+impl ResponseBuilder {
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+    pub fn content(mut self, media_type: &str, content: MediaType) -> Self {
+        self.content.insert(media_type.to_owned(), content);
+        self
+    }
+    pub fn header(mut self, name: impl Into<String>, header: Referenceable<Header>) -> Self {
+        self.headers.insert(name.into(), header);
+        self
+    }
+    pub fn link(mut self, name: impl Into<String>, link: Referenceable<Link>) -> Self {
+        self.links.insert(name.into(), link);
+        self
+    }
+    pub fn build(self) -> Response {
+        Response {
+            description: self.description,
+            headers: non_empty(self.headers),
+            content: non_empty(self.content),
+            links: non_empty(self.links),
+            extras: None,
+        }
+    }
+}
+
+/// start building a response with the given description
+///
+/// ```rust
+/// use oas::builders::response;
+/// use oas::{Header, Link, Referenceable, Schema};
+///
+/// let ok = response("the user")
+///     .header(
+///         "X-Rate-Limit",
+///         Referenceable::Data(Header {
+///             description: Some("calls per hour allowed by the user".to_string()),
+///             schema: Some(Referenceable::Data(Schema {
+///                 _type: Some("integer".to_string()),
+///                 ..Default::default()
+///             })),
+///             ..Default::default()
+///         }),
+///     )
+///     .link(
+///         "userRepositories",
+///         Referenceable::Data(Link::new().with_operation_id("getRepositoriesByOwner")),
+///     )
+///     .build();
+/// assert!(ok.headers.unwrap().contains_key("X-Rate-Limit"));
+/// assert!(ok.links.unwrap().contains_key("userRepositories"));
+/// assert!(ok.content.is_none());
+/// ```
+pub fn response(description: impl Into<String>) -> ResponseBuilder {
+    ResponseBuilder {
+        description: description.into(),
+        headers: Map::new(),
+        content: Map::new(),
+        links: Map::new(),
+    }
+}
+
+/// Builder for [`Operation`], created by [`operation`] or one of the per-method shortcuts.
+#[derive(Debug, Clone, Default)]
+pub struct OperationBuilder {
+    operation: Operation,
+}
+
+impl OperationBuilder {
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.add_tag(tag);
+        self
+    }
+    pub fn summary(mut self, summary: impl Into<String>) -> Self {
+        self.operation.summary = Some(summary.into());
+        self
+    }
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.operation.description = Some(description.into());
+        self
+    }
+    pub fn operation_id(mut self, operation_id: impl Into<String>) -> Self {
+        self.operation.operation_id = Some(operation_id.into());
+        self
+    }
+    pub fn parameter(mut self, parameter: Referenceable<Parameter>) -> Self {
+        self.operation.add_parameter(parameter);
+        self
+    }
+    pub fn request_body(mut self, request_body: Referenceable<RequestBody>) -> Self {
+        self.operation.request_body = Some(request_body);
+        self
+    }
+    pub fn response(
+        mut self,
+        status: impl Into<String>,
+        response: Referenceable<Response>,
+    ) -> Self {
+        self.operation.add_response(status, response);
+        self
+    }
+    pub fn default_response(mut self, response: Referenceable<Response>) -> Self {
+        self.operation.responses.default = Some(response);
+        self
+    }
+    pub fn deprecated(mut self, deprecated: bool) -> Self {
+        self.operation.deprecated = Some(deprecated);
+        self
+    }
+    /// Add a response in place, for building inside a loop without reassigning the builder.
+    ///
+    /// ```rust
+    /// use oas::builders::operation;
+    /// use oas::{Referenceable, Response};
+    ///
+    /// let mut builder = operation().summary("list pets");
+    /// for (status, description) in [("200", "the pets"), ("400", "bad query"), ("500", "failure")] {
+    ///     builder.add_response(status, Referenceable::Data(Response::new(description)));
+    /// }
+    /// builder.add_tag("pets");
+    /// let op = builder.build();
+    /// assert_eq!(op.responses.data.len(), 3);
+    /// assert_eq!(op.tags, Some(vec!["pets".to_string()]));
+    /// ```
+    pub fn add_response(
+        &mut self,
+        status: impl Into<String>,
+        response: Referenceable<Response>,
+    ) -> &mut Self {
+        self.operation.add_response(status, response);
+        self
+    }
+    /// add a parameter in place
+    pub fn add_parameter(&mut self, parameter: Referenceable<Parameter>) -> &mut Self {
+        self.operation.add_parameter(parameter);
+        self
+    }
+    /// add a tag in place
+    pub fn add_tag(&mut self, tag: impl Into<String>) -> &mut Self {
+        self.operation
+            .tags
+            .get_or_insert_with(Vec::new)
+            .push(tag.into());
+        self
+    }
+    pub fn build(self) -> Operation {
+        self.operation
+    }
+    /// Build the operation, failing if it declares no response at all as the specification requires at least one.
+    ///
+    /// ```rust
+    /// use oas::builders::{operation, BuildError};
+    /// use oas::{Referenceable, Response};
+    ///
+    /// assert_eq!(operation().try_build(), Err(BuildError::NoResponses));
+    /// let op = operation()
+    ///     .default_response(Referenceable::Data(Response::new("any response")))
+    ///     .try_build();
+    /// assert!(op.is_ok());
+    /// ```
+    pub fn try_build(self) -> Result<Operation, BuildError> {
+        let responses = &self.operation.responses;
+        if responses.data.is_empty() && responses.default.is_none() {
+            return Err(BuildError::NoResponses);
+        }
+        Ok(self.operation)
+    }
+}
+
+/// start building an operation without any response
+pub fn operation() -> OperationBuilder {
+    OperationBuilder::default()
+}
+
+fn with_responses(summary: impl Into<String>, responses: &[(&str, &str)]) -> OperationBuilder {
+    responses.iter().fold(
+        operation().summary(summary),
+        |builder, (status, description)| {
+            builder.response(*status, Referenceable::Data(Response::new(*description)))
+        },
+    )
+}
+
+/// a GET operation responding `200` and `404`
+///
+/// ```rust
+/// let op = oas::builders::get("get a pet").build();
+/// assert!(op.responses.data.contains_key("200"));
+/// assert!(op.responses.data.contains_key("404"));
+/// ```
+pub fn get(summary: impl Into<String>) -> OperationBuilder {
+    with_responses(
+        summary,
+        &[("200", "Successful response"), ("404", "Not found")],
+    )
+}
+
+/// a POST operation responding `201` and `400`
+///
+/// ```rust
+/// let op = oas::builders::post("create a pet").build();
+/// assert!(op.responses.data.contains_key("201"));
+/// assert!(op.responses.data.contains_key("400"));
+/// ```
+pub fn post(summary: impl Into<String>) -> OperationBuilder {
+    with_responses(summary, &[("201", "Created"), ("400", "Bad request")])
+}
+
+/// a PUT operation responding `200` and `404`
+///
+/// ```rust
+/// let op = oas::builders::put("replace a pet").build();
+/// assert!(op.responses.data.contains_key("200"));
+/// assert!(op.responses.data.contains_key("404"));
+/// ```
+pub fn put(summary: impl Into<String>) -> OperationBuilder {
+    with_responses(
+        summary,
+        &[("200", "Successful response"), ("404", "Not found")],
+    )
+}
+
+/// a DELETE operation responding `204` and `404`
+///
+/// ```rust
+/// let op = oas::builders::delete("delete a pet").build();
+/// assert!(op.responses.data.contains_key("204"));
+/// assert!(op.responses.data.contains_key("404"));
+/// ```
+pub fn delete(summary: impl Into<String>) -> OperationBuilder {
+    with_responses(summary, &[("204", "No content"), ("404", "Not found")])
+}
+
+/// a PATCH operation responding `200` and `404`
+///
+/// ```rust
+/// let op = oas::builders::patch("update a pet partially").build();
+/// assert_eq!(op.summary.as_deref(), Some("update a pet partially"));
+/// assert!(op.responses.data.contains_key("200"));
+/// assert!(op.responses.data.contains_key("404"));
+/// ```
+pub fn patch(summary: impl Into<String>) -> OperationBuilder {
+    with_responses(
+        summary,
+        &[("200", "Successful response"), ("404", "Not found")],
+    )
+}
+
+/// an OPTIONS operation responding `204`
+pub fn options(summary: impl Into<String>) -> OperationBuilder {
+    with_responses(summary, &[("204", "Allowed methods")])
+}
+
+/// a HEAD operation responding `200` and `404`
+pub fn head(summary: impl Into<String>) -> OperationBuilder {
+    with_responses(
+        summary,
+        &[("200", "Successful response"), ("404", "Not found")],
+    )
+}
+
+/// a TRACE operation responding `200`
+pub fn trace(summary: impl Into<String>) -> OperationBuilder {
+    with_responses(summary, &[("200", "Successful response")])
+}
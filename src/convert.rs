@@ -0,0 +1,735 @@
+//! Conversion of legacy Swagger 2.0 documents into OpenAPI 3.0.
+
+use crate::{Any, OpenAPIV3};
+use serde_json::{json, Map as JsonMap};
+use std::fmt;
+
+/// Why a Swagger 2.0 document could not be converted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConvertError {
+    /// JSON pointer into the source document, empty for the root.
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+impl std::error::Error for ConvertError {}
+
+fn error(path: impl Into<String>, message: impl Into<String>) -> ConvertError {
+    ConvertError {
+        path: path.into(),
+        message: message.into(),
+    }
+}
+
+const METHODS: [&str; 7] = ["get", "put", "post", "delete", "options", "head", "patch"];
+const SCHEMA_KEYWORDS: [&str; 16] = [
+    "type",
+    "format",
+    "items",
+    "enum",
+    "default",
+    "maximum",
+    "exclusiveMaximum",
+    "minimum",
+    "exclusiveMinimum",
+    "maxLength",
+    "minLength",
+    "pattern",
+    "maxItems",
+    "minItems",
+    "uniqueItems",
+    "multipleOf",
+];
+
+/// Convert a Swagger 2.0 document into an [`OpenAPIV3`].
+///
+/// `host`, `basePath` and `schemes` become `servers`, `definitions` become `components.schemas`, body and form parameters become request bodies, response schemas become content for every `produces` media type and `#/definitions/...` references are rewritten to `#/components/schemas/...`. Constructs without a 3.0 counterpart, such as `tsv` collection formats, are reported as a [`ConvertError`].
+pub fn from_swagger2(value: &Any) -> Result<OpenAPIV3, ConvertError> {
+    let root = value
+        .as_object()
+        .ok_or_else(|| error("", "expected an object"))?;
+    match root.get("swagger").and_then(Any::as_str) {
+        Some("2.0") => {}
+        other => {
+            return Err(error(
+                "/swagger",
+                format!("expected swagger version 2.0, found {:?}", other),
+            ))
+        }
+    }
+    let converter = Converter {
+        consumes: strings(root.get("consumes")),
+        produces: strings(root.get("produces")),
+        body_parameters: root
+            .get("parameters")
+            .and_then(Any::as_object)
+            .map(|parameters| {
+                parameters
+                    .iter()
+                    .filter(|(_, parameter)| parameter["in"] == "body")
+                    .map(|(name, _)| name.clone())
+                    .collect()
+            })
+            .unwrap_or_default(),
+    };
+
+    let mut document = JsonMap::new();
+    document.insert("openapi".to_owned(), json!("3.0.3"));
+    for (key, value) in root {
+        match key.as_str() {
+            "info" | "tags" | "externalDocs" | "security" => {
+                document.insert(key.clone(), value.clone());
+            }
+            key if key.starts_with("x-") => {
+                document.insert(key.to_owned(), value.clone());
+            }
+            _ => {}
+        }
+    }
+    if let Some(servers) = servers(root) {
+        document.insert("servers".to_owned(), servers);
+    }
+
+    let mut paths = JsonMap::new();
+    for (path, item) in object(root.get("paths"), "/paths")?.into_iter().flatten() {
+        let pointer = format!("/paths/{}", escape(path));
+        paths.insert(path.clone(), converter.path_item(item, &pointer)?);
+    }
+    document.insert("paths".to_owned(), Any::Object(paths));
+
+    let mut components = JsonMap::new();
+    if let Some(definitions) = root.get("definitions") {
+        components.insert("schemas".to_owned(), definitions.clone());
+    }
+    let mut parameters = JsonMap::new();
+    let mut request_bodies = JsonMap::new();
+    for (name, parameter) in object(root.get("parameters"), "/parameters")?
+        .into_iter()
+        .flatten()
+    {
+        let pointer = format!("/parameters/{}", escape(name));
+        if converter.body_parameters.contains(name) {
+            let body = converter.request_body(&[parameter], &converter.consumes, &pointer)?;
+            request_bodies.insert(name.clone(), body);
+        } else {
+            parameters.insert(name.clone(), converter.parameter(parameter, &pointer)?);
+        }
+    }
+    let mut responses = JsonMap::new();
+    for (name, response) in object(root.get("responses"), "/responses")?
+        .into_iter()
+        .flatten()
+    {
+        let pointer = format!("/responses/{}", escape(name));
+        responses.insert(
+            name.clone(),
+            converter.response(response, &converter.produces, &pointer)?,
+        );
+    }
+    let mut security_schemes = JsonMap::new();
+    for (name, scheme) in object(root.get("securityDefinitions"), "/securityDefinitions")?
+        .into_iter()
+        .flatten()
+    {
+        let pointer = format!("/securityDefinitions/{}", escape(name));
+        security_schemes.insert(name.clone(), security_scheme(scheme, &pointer)?);
+    }
+    for (key, map) in [
+        ("parameters", parameters),
+        ("requestBodies", request_bodies),
+        ("responses", responses),
+        ("securitySchemes", security_schemes),
+    ] {
+        if !map.is_empty() {
+            components.insert(key.to_owned(), Any::Object(map));
+        }
+    }
+    if !components.is_empty() {
+        document.insert("components".to_owned(), Any::Object(components));
+    }
+
+    let mut document = Any::Object(document);
+    rewrite(&mut document);
+    serde_json::from_value(document).map_err(|e| error("", e.to_string()))
+}
+
+struct Converter {
+    consumes: Vec<String>,
+    produces: Vec<String>,
+    body_parameters: Vec<String>,
+}
+
+impl Converter {
+    fn path_item(&self, item: &Any, pointer: &str) -> Result<Any, ConvertError> {
+        let item = item
+            .as_object()
+            .ok_or_else(|| error(pointer, "expected an object"))?;
+        let mut converted = JsonMap::new();
+        for (key, value) in item {
+            let pointer = format!("{}/{}", pointer, key);
+            match key.as_str() {
+                "parameters" => {
+                    let parameters = array(value, &pointer)?;
+                    if parameters.iter().any(|it| self.is_body(it)) {
+                        return Err(error(
+                            pointer,
+                            "body and form parameters on a path item are not supported",
+                        ));
+                    }
+                    let parameters = parameters
+                        .iter()
+                        .enumerate()
+                        .map(|(index, it)| self.parameter(it, &format!("{}/{}", pointer, index)))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    converted.insert(key.clone(), Any::Array(parameters));
+                }
+                method if METHODS.contains(&method) => {
+                    converted.insert(key.clone(), self.operation(value, &pointer)?);
+                }
+                _ => {
+                    converted.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        Ok(Any::Object(converted))
+    }
+
+    fn operation(&self, operation: &Any, pointer: &str) -> Result<Any, ConvertError> {
+        let operation = operation
+            .as_object()
+            .ok_or_else(|| error(pointer, "expected an object"))?;
+        let consumes = operation
+            .get("consumes")
+            .map(|it| strings(Some(it)))
+            .unwrap_or_else(|| self.consumes.clone());
+        let produces = operation
+            .get("produces")
+            .map(|it| strings(Some(it)))
+            .unwrap_or_else(|| self.produces.clone());
+        let mut converted = JsonMap::new();
+        for (key, value) in operation {
+            let pointer = format!("{}/{}", pointer, key);
+            match key.as_str() {
+                "consumes" | "produces" | "schemes" => {}
+                "parameters" => {
+                    let mut parameters = vec![];
+                    let mut body = vec![];
+                    for (index, parameter) in array(value, &pointer)?.iter().enumerate() {
+                        let pointer = format!("{}/{}", pointer, index);
+                        if let Some(name) = self.body_parameter_reference(parameter) {
+                            let reference = format!("#/components/requestBodies/{}", name);
+                            converted
+                                .insert("requestBody".to_owned(), json!({ "$ref": reference }));
+                        } else if self.is_body(parameter) {
+                            body.push(parameter);
+                        } else {
+                            parameters.push(self.parameter(parameter, &pointer)?);
+                        }
+                    }
+                    if !body.is_empty() {
+                        converted.insert(
+                            "requestBody".to_owned(),
+                            self.request_body(&body, &consumes, &pointer)?,
+                        );
+                    }
+                    if !parameters.is_empty() {
+                        converted.insert(key.clone(), Any::Array(parameters));
+                    }
+                }
+                "responses" => {
+                    let mut responses = JsonMap::new();
+                    for (status, response) in object(Some(value), &pointer)?.into_iter().flatten() {
+                        let pointer = format!("{}/{}", pointer, status);
+                        responses.insert(
+                            status.clone(),
+                            self.response(response, &produces, &pointer)?,
+                        );
+                    }
+                    converted.insert(key.clone(), Any::Object(responses));
+                }
+                _ => {
+                    converted.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        Ok(Any::Object(converted))
+    }
+
+    fn is_body(&self, parameter: &Any) -> bool {
+        matches!(parameter["in"].as_str(), Some("body" | "formData"))
+    }
+
+    fn body_parameter_reference(&self, parameter: &Any) -> Option<String> {
+        let name = parameter["$ref"].as_str()?.strip_prefix("#/parameters/")?;
+        self.body_parameters
+            .iter()
+            .find(|it| it.as_str() == name)
+            .cloned()
+    }
+
+    fn parameter(&self, parameter: &Any, pointer: &str) -> Result<Any, ConvertError> {
+        let source = parameter
+            .as_object()
+            .ok_or_else(|| error(pointer, "expected an object"))?;
+        if source.contains_key("$ref") {
+            return Ok(parameter.clone());
+        }
+        if self.is_body(parameter) {
+            return Err(error(pointer, "body and form parameters can not be reused"));
+        }
+        let mut converted = JsonMap::new();
+        for (key, value) in source {
+            match key.as_str() {
+                "name" | "in" | "description" | "required" | "allowEmptyValue" => {
+                    converted.insert(key.clone(), value.clone());
+                }
+                key if key.starts_with("x-") => {
+                    converted.insert(key.to_owned(), value.clone());
+                }
+                _ => {}
+            }
+        }
+        match source.get("collectionFormat").and_then(Any::as_str) {
+            None | Some("csv") => {
+                if source.get("type") == Some(&json!("array")) {
+                    let style = if source["in"] == "query" {
+                        "form"
+                    } else {
+                        "simple"
+                    };
+                    converted.insert("style".to_owned(), json!(style));
+                    converted.insert("explode".to_owned(), json!(false));
+                }
+            }
+            Some("multi") => {
+                converted.insert("style".to_owned(), json!("form"));
+                converted.insert("explode".to_owned(), json!(true));
+            }
+            Some("ssv") => {
+                converted.insert("style".to_owned(), json!("spaceDelimited"));
+            }
+            Some("pipes") => {
+                converted.insert("style".to_owned(), json!("pipeDelimited"));
+            }
+            Some(other) => {
+                return Err(error(
+                    format!("{}/collectionFormat", pointer),
+                    format!(
+                        "collection format `{}` has no OpenAPI 3.0 equivalent",
+                        other
+                    ),
+                ))
+            }
+        }
+        converted.insert("schema".to_owned(), schema_of(source));
+        Ok(Any::Object(converted))
+    }
+
+    fn request_body(
+        &self,
+        parameters: &[&Any],
+        consumes: &[String],
+        pointer: &str,
+    ) -> Result<Any, ConvertError> {
+        let mut body = JsonMap::new();
+        let mut content = JsonMap::new();
+        if let Some(parameter) = parameters.iter().find(|it| it["in"] == "body") {
+            if parameters.len() > 1 {
+                return Err(error(pointer, "body and form parameters can not be mixed"));
+            }
+            for key in ["description", "required"] {
+                if let Some(value) = parameter.get(key) {
+                    body.insert(key.to_owned(), value.clone());
+                }
+            }
+            let schema = parameter
+                .get("schema")
+                .cloned()
+                .unwrap_or_else(|| json!({}));
+            for media_type in media_types(consumes, "application/json") {
+                content.insert(media_type, json!({ "schema": schema }));
+            }
+        } else {
+            let mut properties = JsonMap::new();
+            let mut required = vec![];
+            for parameter in parameters {
+                let name = parameter["name"]
+                    .as_str()
+                    .ok_or_else(|| error(pointer, "form parameter without a name"))?;
+                let source = parameter.as_object().cloned().unwrap_or_default();
+                let mut schema = schema_of(&source);
+                if let Some(description) = parameter.get("description") {
+                    schema["description"] = description.clone();
+                }
+                properties.insert(name.to_owned(), schema);
+                if parameter["required"] == json!(true) {
+                    required.push(json!(name));
+                }
+            }
+            let has_file = parameters.iter().any(|it| it["type"] == "file");
+            let media_type = if has_file || consumes.iter().any(|it| it == "multipart/form-data") {
+                "multipart/form-data"
+            } else {
+                "application/x-www-form-urlencoded"
+            };
+            let mut schema = json!({"type": "object", "properties": properties});
+            if !required.is_empty() {
+                schema["required"] = Any::Array(required);
+                body.insert("required".to_owned(), json!(true));
+            }
+            content.insert(media_type.to_owned(), json!({ "schema": schema }));
+        }
+        body.insert("content".to_owned(), Any::Object(content));
+        Ok(Any::Object(body))
+    }
+
+    fn response(
+        &self,
+        response: &Any,
+        produces: &[String],
+        pointer: &str,
+    ) -> Result<Any, ConvertError> {
+        let source = response
+            .as_object()
+            .ok_or_else(|| error(pointer, "expected an object"))?;
+        if source.contains_key("$ref") {
+            return Ok(response.clone());
+        }
+        let mut converted = JsonMap::new();
+        let mut content = JsonMap::new();
+        for (key, value) in source {
+            match key.as_str() {
+                "schema" => {
+                    for media_type in media_types(produces, "application/json") {
+                        content.insert(media_type, json!({ "schema": value }));
+                    }
+                }
+                "examples" => {
+                    for (media_type, example) in value.as_object().into_iter().flatten() {
+                        let entry = content
+                            .entry(media_type.clone())
+                            .or_insert_with(|| json!({}));
+                        entry["example"] = example.clone();
+                    }
+                }
+                "headers" => {
+                    let headers: JsonMap<String, Any> = value
+                        .as_object()
+                        .into_iter()
+                        .flatten()
+                        .map(|(name, header)| {
+                            let source = header.as_object().cloned().unwrap_or_default();
+                            let mut converted = json!({ "schema": schema_of(&source) });
+                            if let Some(description) = source.get("description") {
+                                converted["description"] = description.clone();
+                            }
+                            (name.clone(), converted)
+                        })
+                        .collect();
+                    converted.insert(key.clone(), Any::Object(headers));
+                }
+                _ => {
+                    converted.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        if !content.is_empty() {
+            converted.insert("content".to_owned(), Any::Object(content));
+        }
+        Ok(Any::Object(converted))
+    }
+}
+
+fn security_scheme(scheme: &Any, pointer: &str) -> Result<Any, ConvertError> {
+    let mut converted = match scheme["type"].as_str() {
+        Some("basic") => json!({"type": "http", "scheme": "basic"}),
+        Some("apiKey") => json!({"type": "apiKey", "name": scheme["name"], "in": scheme["in"]}),
+        Some("oauth2") => {
+            let flow = match scheme["flow"].as_str() {
+                Some("implicit") => "implicit",
+                Some("accessCode") => "authorizationCode",
+                Some(flow) => {
+                    return Err(error(
+                        format!("{}/flow", pointer),
+                        format!("oauth2 flow `{}` is not supported", flow),
+                    ))
+                }
+                None => return Err(error(pointer, "oauth2 scheme without a flow")),
+            };
+            let mut converted_flow =
+                json!({"scopes": scheme.get("scopes").cloned().unwrap_or_else(|| json!({}))});
+            for key in ["authorizationUrl", "tokenUrl"] {
+                if let Some(url) = scheme.get(key) {
+                    converted_flow[key] = url.clone();
+                }
+            }
+            json!({"type": "oauth2", "flows": { flow: converted_flow }})
+        }
+        other => {
+            return Err(error(
+                format!("{}/type", pointer),
+                format!("unknown security scheme type {:?}", other),
+            ))
+        }
+    };
+    if let Some(description) = scheme.get("description") {
+        converted["description"] = description.clone();
+    }
+    Ok(converted)
+}
+
+fn servers(root: &JsonMap<String, Any>) -> Option<Any> {
+    let host = root.get("host").and_then(Any::as_str);
+    let base_path = root.get("basePath").and_then(Any::as_str).unwrap_or("");
+    let urls: Vec<String> = match host {
+        None if base_path.is_empty() => return None,
+        None => vec![base_path.to_owned()],
+        Some(host) => {
+            let schemes = strings(root.get("schemes"));
+            let schemes = if schemes.is_empty() {
+                vec!["https".to_owned()]
+            } else {
+                schemes
+            };
+            schemes
+                .iter()
+                .map(|scheme| format!("{}://{}{}", scheme, host, base_path))
+                .collect()
+        }
+    };
+    Some(Any::Array(
+        urls.into_iter().map(|url| json!({ "url": url })).collect(),
+    ))
+}
+
+/// the schema described inline by a non-body parameter, header or items object
+fn schema_of(source: &JsonMap<String, Any>) -> Any {
+    let mut schema = JsonMap::new();
+    for keyword in SCHEMA_KEYWORDS {
+        if let Some(value) = source.get(keyword) {
+            let value = match keyword {
+                "items" => schema_of(&value.as_object().cloned().unwrap_or_default()),
+                _ => value.clone(),
+            };
+            schema.insert(keyword.to_owned(), value);
+        }
+    }
+    Any::Object(schema)
+}
+
+/// rewrite references and the schema keywords whose shape changed
+fn rewrite(value: &mut Any) {
+    match value {
+        Any::Object(object) => {
+            if let Some(Any::String(reference)) = object.get_mut("$ref") {
+                for (from, to) in [
+                    ("#/definitions/", "#/components/schemas/"),
+                    ("#/parameters/", "#/components/parameters/"),
+                    ("#/responses/", "#/components/responses/"),
+                ] {
+                    if let Some(name) = reference.strip_prefix(from) {
+                        *reference = format!("{}{}", to, name);
+                    }
+                }
+            }
+            if object.get("type") == Some(&json!("file")) {
+                object.insert("type".to_owned(), json!("string"));
+                object.insert("format".to_owned(), json!("binary"));
+            }
+            if let Some(Any::String(property)) = object.get("discriminator") {
+                let discriminator = json!({ "propertyName": property });
+                object.insert("discriminator".to_owned(), discriminator);
+            }
+            if let Some(nullable) = object.remove("x-nullable") {
+                object.insert("nullable".to_owned(), nullable);
+            }
+            for (key, child) in object.iter_mut() {
+                if key != "example" {
+                    rewrite(child);
+                }
+            }
+        }
+        Any::Array(elements) => elements.iter_mut().for_each(rewrite),
+        _ => {}
+    }
+}
+
+fn object<'a>(
+    value: Option<&'a Any>,
+    pointer: &str,
+) -> Result<Option<&'a JsonMap<String, Any>>, ConvertError> {
+    value
+        .map(|value| {
+            value
+                .as_object()
+                .ok_or_else(|| error(pointer, "expected an object"))
+        })
+        .transpose()
+}
+
+fn array<'a>(value: &'a Any, pointer: &str) -> Result<&'a Vec<Any>, ConvertError> {
+    value
+        .as_array()
+        .ok_or_else(|| error(pointer, "expected an array"))
+}
+
+fn strings(value: Option<&Any>) -> Vec<String> {
+    value
+        .and_then(Any::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|it| it.as_str().map(str::to_owned))
+        .collect()
+}
+
+fn media_types(declared: &[String], fallback: &str) -> Vec<String> {
+    if declared.is_empty() {
+        vec![fallback.to_owned()]
+    } else {
+        declared.to_vec()
+    }
+}
+
+fn escape(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod test {
+    use super::from_swagger2;
+    use crate::{ParameterIn, Referenceable};
+    use serde_json::json;
+
+    fn petstore() -> serde_json::Value {
+        json!({
+            "swagger": "2.0",
+            "info": {"title": "Swagger Petstore", "version": "1.0.0"},
+            "host": "petstore.swagger.io",
+            "basePath": "/v1",
+            "schemes": ["http"],
+            "consumes": ["application/json"],
+            "produces": ["application/json"],
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "operationId": "listPets",
+                        "parameters": [{
+                            "name": "tags", "in": "query", "type": "array",
+                            "items": {"type": "string"}, "collectionFormat": "multi"
+                        }],
+                        "responses": {
+                            "200": {
+                                "description": "pets",
+                                "headers": {"x-next": {"type": "string", "description": "next page"}},
+                                "schema": {"type": "array", "items": {"$ref": "#/definitions/Pet"}}
+                            },
+                            "default": {"$ref": "#/responses/Error"}
+                        }
+                    },
+                    "post": {
+                        "operationId": "createPet",
+                        "parameters": [{
+                            "name": "pet", "in": "body", "required": true,
+                            "schema": {"$ref": "#/definitions/Pet"}
+                        }],
+                        "responses": {"201": {"description": "created"}}
+                    }
+                }
+            },
+            "definitions": {
+                "Pet": {
+                    "type": "object",
+                    "required": ["id"],
+                    "properties": {
+                        "id": {"type": "integer", "format": "int64"},
+                        "photo": {"type": "file"}
+                    }
+                }
+            },
+            "responses": {"Error": {"description": "unexpected error"}},
+            "securityDefinitions": {"key": {"type": "apiKey", "name": "X-Key", "in": "header"}}
+        })
+    }
+
+    #[test]
+    fn should_convert_minimal_petstore() {
+        let spec = from_swagger2(&petstore()).unwrap();
+        assert_eq!(spec.openapi, "3.0.3");
+        assert_eq!(
+            spec.servers.as_ref().unwrap()[0].url,
+            "http://petstore.swagger.io/v1"
+        );
+
+        let pets = &spec.paths["/pets"];
+        let list = pets.get.as_ref().unwrap();
+        let tags = list.parameters.as_ref().unwrap()[0].as_data().unwrap();
+        assert_eq!(tags._in, ParameterIn::Query);
+        assert_eq!(tags.explode, Some(true));
+        let ok = list.responses.data["200"].as_data().unwrap();
+        let schema = ok.content.as_ref().unwrap()["application/json"]
+            .schema
+            .as_ref()
+            .unwrap()
+            .as_data()
+            .unwrap();
+        assert_eq!(
+            schema
+                .items
+                .as_deref()
+                .unwrap()
+                .as_reference()
+                .unwrap()
+                ._ref,
+            "#/components/schemas/Pet"
+        );
+        assert!(ok.headers.as_ref().unwrap().contains_key("x-next"));
+        assert_eq!(
+            list.responses
+                .default
+                .as_ref()
+                .unwrap()
+                .as_reference()
+                .unwrap()
+                ._ref,
+            "#/components/responses/Error"
+        );
+
+        let create = pets.post.as_ref().unwrap();
+        let body = create.request_body.as_ref().unwrap().as_data().unwrap();
+        assert_eq!(body.required, Some(true));
+        assert!(body.content.contains_key("application/json"));
+
+        let components = spec.components.as_ref().unwrap();
+        let pet = match &components.schemas.as_ref().unwrap()["Pet"] {
+            Referenceable::Data(pet) => pet,
+            _ => panic!("expected an inline schema"),
+        };
+        let photo = pet.properties.as_ref().unwrap()["photo"].as_data().unwrap();
+        assert_eq!(photo.format.as_deref(), Some("binary"));
+        assert!(components
+            .security_schemes
+            .as_ref()
+            .unwrap()
+            .contains_key("key"));
+    }
+
+    #[test]
+    fn should_report_unsupported_constructs_with_context() {
+        let mut document = petstore();
+        document["paths"]["/pets"]["get"]["parameters"][0]["collectionFormat"] = json!("tsv");
+        let error = from_swagger2(&document).unwrap_err();
+        assert_eq!(
+            error.path,
+            "/paths/~1pets/get/parameters/0/collectionFormat"
+        );
+
+        let error = from_swagger2(&json!({"swagger": "1.2"})).unwrap_err();
+        assert_eq!(error.path, "/swagger");
+    }
+}
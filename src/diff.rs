@@ -0,0 +1,408 @@
+//! Structural comparison of two documents, classifying changes by backward compatibility.
+
+use crate::{HttpMethod, OpenAPIV3, Operation, Parameter, ParameterIn, PathItem};
+use std::collections::BTreeMap;
+
+/// A single difference between two documents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    PathAdded {
+        path: String,
+    },
+    PathRemoved {
+        path: String,
+    },
+    OperationAdded {
+        path: String,
+        method: HttpMethod,
+    },
+    OperationRemoved {
+        path: String,
+        method: HttpMethod,
+    },
+    ResponseRemoved {
+        path: String,
+        method: HttpMethod,
+        status: String,
+    },
+    ParameterAdded {
+        path: String,
+        method: HttpMethod,
+        name: String,
+        location: ParameterIn,
+        required: bool,
+    },
+    ParameterBecameRequired {
+        path: String,
+        method: HttpMethod,
+        name: String,
+        location: ParameterIn,
+    },
+}
+
+impl Change {
+    /// whether existing clients of the old document may break against the new one
+    pub fn is_breaking(&self) -> bool {
+        match self {
+            Change::PathAdded { .. } | Change::OperationAdded { .. } => false,
+            Change::PathRemoved { .. }
+            | Change::OperationRemoved { .. }
+            | Change::ResponseRemoved { .. }
+            | Change::ParameterBecameRequired { .. } => true,
+            Change::ParameterAdded { required, .. } => *required,
+        }
+    }
+    /// the path the change happened on
+    pub fn path(&self) -> &str {
+        match self {
+            Change::PathAdded { path }
+            | Change::PathRemoved { path }
+            | Change::OperationAdded { path, .. }
+            | Change::OperationRemoved { path, .. }
+            | Change::ResponseRemoved { path, .. }
+            | Change::ParameterAdded { path, .. }
+            | Change::ParameterBecameRequired { path, .. } => path,
+        }
+    }
+}
+
+/// The changes between two documents, split by backward compatibility.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpecDiff {
+    pub breaking: Vec<Change>,
+    pub non_breaking: Vec<Change>,
+}
+
+impl SpecDiff {
+    pub fn is_empty(&self) -> bool {
+        self.breaking.is_empty() && self.non_breaking.is_empty()
+    }
+    pub fn has_breaking_changes(&self) -> bool {
+        !self.breaking.is_empty()
+    }
+    fn push(&mut self, change: Change) {
+        if change.is_breaking() {
+            self.breaking.push(change);
+        } else {
+            self.non_breaking.push(change);
+        }
+    }
+}
+
+/// Compare `old` against `new`, reporting added and removed paths and operations, removed responses and parameters that are new or became required.
+pub fn diff(old: &OpenAPIV3, new: &OpenAPIV3) -> SpecDiff {
+    let mut result = SpecDiff::default();
+    for path in old.paths.keys() {
+        if !new.paths.contains_key(path) {
+            result.push(Change::PathRemoved { path: path.clone() });
+        }
+    }
+    for (path, new_item) in new.paths.iter() {
+        let old_item = match old.paths.get(path) {
+            Some(item) => item,
+            None => {
+                result.push(Change::PathAdded { path: path.clone() });
+                continue;
+            }
+        };
+        for method in HttpMethod::ALL {
+            match (old_item.operation(method), new_item.operation(method)) {
+                (Some(_), None) => result.push(Change::OperationRemoved {
+                    path: path.clone(),
+                    method,
+                }),
+                (None, Some(_)) => result.push(Change::OperationAdded {
+                    path: path.clone(),
+                    method,
+                }),
+                (Some(old_operation), Some(new_operation)) => {
+                    let context = OperationContext { path, method };
+                    context.diff_responses(old_operation, new_operation, &mut result);
+                    context.diff_parameters(
+                        (old, old_item, old_operation),
+                        (new, new_item, new_operation),
+                        &mut result,
+                    );
+                }
+                (None, None) => {}
+            }
+        }
+    }
+    result
+}
+
+/// Render the changes as a report for humans, e.g. in CI logs.
+///
+/// Changes are grouped under the path they happened on, paths sorted. Each line starts with `+` for an addition, `-` for a removal or `~` for a modification, and breaking changes are marked as such. An empty diff renders as an empty string.
+///
+/// ```text
+/// /users
+///   ~ query parameter 'limit' of GET /users now required (breaking)
+///   - POST /users (breaking)
+/// ```
+pub fn format_diff(diff: &SpecDiff) -> String {
+    let mut by_path: BTreeMap<&str, Vec<&Change>> = BTreeMap::new();
+    for change in diff.breaking.iter().chain(diff.non_breaking.iter()) {
+        by_path.entry(change.path()).or_default().push(change);
+    }
+    let mut report = String::new();
+    for (path, changes) in by_path {
+        report.push_str(path);
+        report.push('\n');
+        for change in changes {
+            let line = match change {
+                Change::PathAdded { path } => format!("+ {}", path),
+                Change::PathRemoved { path } => format!("- {}", path),
+                Change::OperationAdded { path, method } => format!("+ {} {}", upper(method), path),
+                Change::OperationRemoved { path, method } => {
+                    format!("- {} {}", upper(method), path)
+                }
+                Change::ResponseRemoved {
+                    path,
+                    method,
+                    status,
+                } => format!("- response {} on {} {}", status, upper(method), path),
+                Change::ParameterAdded {
+                    path,
+                    method,
+                    name,
+                    location,
+                    required,
+                } => format!(
+                    "+ {}{} parameter '{}' on {} {}",
+                    if *required { "required " } else { "" },
+                    location.as_str(),
+                    name,
+                    upper(method),
+                    path
+                ),
+                Change::ParameterBecameRequired {
+                    path,
+                    method,
+                    name,
+                    location,
+                } => format!(
+                    "~ {} parameter '{}' of {} {} now required",
+                    location.as_str(),
+                    name,
+                    upper(method),
+                    path
+                ),
+            };
+            report.push_str("  ");
+            report.push_str(&line);
+            if change.is_breaking() {
+                report.push_str(" (breaking)");
+            }
+            report.push('\n');
+        }
+    }
+    report
+}
+
+fn upper(method: &HttpMethod) -> String {
+    method.as_str().to_ascii_uppercase()
+}
+
+struct OperationContext<'a> {
+    path: &'a str,
+    method: HttpMethod,
+}
+
+impl OperationContext<'_> {
+    fn diff_responses(&self, old: &Operation, new: &Operation, result: &mut SpecDiff) {
+        let statuses = |operation: &Operation| {
+            let mut statuses: Vec<String> = operation.responses.data.keys().cloned().collect();
+            if operation.responses.default.is_some() {
+                statuses.push("default".to_string());
+            }
+            statuses
+        };
+        let new_statuses = statuses(new);
+        for status in statuses(old) {
+            if !new_statuses.contains(&status) {
+                result.push(Change::ResponseRemoved {
+                    path: self.path.to_owned(),
+                    method: self.method,
+                    status,
+                });
+            }
+        }
+    }
+
+    fn diff_parameters(
+        &self,
+        old: (&OpenAPIV3, &PathItem, &Operation),
+        new: (&OpenAPIV3, &PathItem, &Operation),
+        result: &mut SpecDiff,
+    ) {
+        let old_parameters = parameters(old.0, old.1, old.2);
+        for parameter in parameters(new.0, new.1, new.2) {
+            let required = parameter.required == Some(true);
+            match old_parameters
+                .iter()
+                .find(|it| it.name == parameter.name && it._in == parameter._in)
+            {
+                None => result.push(Change::ParameterAdded {
+                    path: self.path.to_owned(),
+                    method: self.method,
+                    name: parameter.name.clone(),
+                    location: parameter._in,
+                    required,
+                }),
+                Some(previous) if required && previous.required != Some(true) => {
+                    result.push(Change::ParameterBecameRequired {
+                        path: self.path.to_owned(),
+                        method: self.method,
+                        name: parameter.name.clone(),
+                        location: parameter._in,
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+    }
+}
+
+/// path level parameters overridden by operation level ones, skipping unresolvable references
+fn parameters<'a>(
+    spec: &'a OpenAPIV3,
+    item: &'a PathItem,
+    operation: &'a Operation,
+) -> Vec<&'a Parameter> {
+    let mut result: Vec<&Parameter> = vec![];
+    let declared = item.parameters.iter().chain(operation.parameters.iter());
+    for parameter in declared.flatten().filter_map(|it| spec.resolve(it)) {
+        result.retain(|it| !(it.name == parameter.name && it._in == parameter._in));
+        result.push(parameter);
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::{diff, format_diff, Change};
+    use crate::{HttpMethod, OpenAPIV3, ParameterIn};
+    use serde_json::json;
+
+    fn base() -> serde_json::Value {
+        json!({
+            "openapi": "3.0.0",
+            "info": {"title": "users", "version": "1.0.0"},
+            "paths": {
+                "/users": {
+                    "get": {
+                        "parameters": [{"$ref": "#/components/parameters/limit"}],
+                        "responses": {"200": {"description": "users"}}
+                    },
+                    "post": {"responses": {"201": {"description": "created"}}}
+                },
+                "/users/{id}": {
+                    "get": {"responses": {"200": {"description": "user"}, "404": {"description": "missing"}}}
+                }
+            },
+            "components": {
+                "parameters": {"limit": {"name": "limit", "in": "query"}}
+            }
+        })
+    }
+
+    fn spec(value: serde_json::Value) -> OpenAPIV3 {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn should_report_removed_endpoint_as_breaking() {
+        let mut changed = base();
+        changed["paths"]
+            .as_object_mut()
+            .unwrap()
+            .remove("/users/{id}");
+        changed["paths"]["/users"]
+            .as_object_mut()
+            .unwrap()
+            .remove("post");
+        let result = diff(&spec(base()), &spec(changed));
+        assert_eq!(
+            result.breaking,
+            vec![
+                Change::PathRemoved {
+                    path: "/users/{id}".to_string()
+                },
+                Change::OperationRemoved {
+                    path: "/users".to_string(),
+                    method: HttpMethod::Post
+                },
+            ]
+        );
+        assert!(result.non_breaking.is_empty());
+    }
+
+    #[test]
+    fn should_report_optional_query_parameter_as_non_breaking() {
+        let mut changed = base();
+        changed["paths"]["/users"]["get"]["parameters"]
+            .as_array_mut()
+            .unwrap()
+            .push(json!({"name": "offset", "in": "query", "required": false}));
+        let result = diff(&spec(base()), &spec(changed));
+        assert!(!result.has_breaking_changes());
+        assert_eq!(
+            result.non_breaking,
+            vec![Change::ParameterAdded {
+                path: "/users".to_string(),
+                method: HttpMethod::Get,
+                name: "offset".to_string(),
+                location: ParameterIn::Query,
+                required: false
+            }]
+        );
+    }
+
+    #[test]
+    fn should_report_required_parameter_and_removed_response() {
+        let mut changed = base();
+        changed["components"]["parameters"]["limit"]["required"] = json!(true);
+        changed["paths"]["/users/{id}"]["get"]["responses"]
+            .as_object_mut()
+            .unwrap()
+            .remove("404");
+        let result = diff(&spec(base()), &spec(changed));
+        assert_eq!(result.breaking.len(), 2);
+        assert!(result
+            .breaking
+            .iter()
+            .any(|change| matches!(change, Change::ParameterBecameRequired { name, .. } if name == "limit")));
+        assert!(result.breaking.iter().any(
+            |change| matches!(change, Change::ResponseRemoved { status, .. } if status == "404")
+        ));
+    }
+
+    #[test]
+    fn should_format_changes_by_path() {
+        let mut changed = base();
+        changed["paths"]["/users"]
+            .as_object_mut()
+            .unwrap()
+            .remove("post");
+        changed["paths"]["/users/{id}"]["delete"] =
+            json!({"responses": {"204": {"description": "deleted"}}});
+        changed["paths"]["/users/{id}"]["get"]["responses"]
+            .as_object_mut()
+            .unwrap()
+            .remove("404");
+        changed["components"]["parameters"]["limit"]["required"] = json!(true);
+
+        let report = format_diff(&diff(&spec(base()), &spec(changed)));
+        assert_eq!(
+            report,
+            "/users\n\
+             \x20 ~ query parameter 'limit' of GET /users now required (breaking)\n\
+             \x20 - POST /users (breaking)\n\
+             /users/{id}\n\
+             \x20 - response 404 on GET /users/{id} (breaking)\n\
+             \x20 + DELETE /users/{id}\n"
+        );
+        assert_eq!(format_diff(&diff(&spec(base()), &spec(base()))), "");
+    }
+}
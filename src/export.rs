@@ -0,0 +1,1236 @@
+//! Renderings of a document for documentation and other tooling.
+
+use crate::transform::unique_name;
+use crate::{
+    Any, Components, HttpMethod, KindUsage, Map, OpenAPIV3, Operation, Parameter, ParameterIn,
+    Referenceable, Schema, Server, Tag,
+};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Build a copy-pasteable `curl` command line for an operation.
+///
+/// Required path, query and header parameters are filled with their example value, falling back to the example derived from their schema or to `{name}`. Path and query values are percent-encoded. A JSON request body is rendered from the media type example or the schema's example value. Referenced parameters and bodies can not be resolved here and are skipped.
+pub fn curl_example(server: &Server, path: &str, method: HttpMethod, op: &Operation) -> String {
+    let parameters: Vec<&Parameter> = op
+        .parameters
+        .iter()
+        .flatten()
+        .filter_map(|parameter| parameter.as_data())
+        .collect();
+
+    let mut url = format!("{}{}", server.url.trim_end_matches('/'), path);
+    for parameter in parameters
+        .iter()
+        .filter(|it| it._in == ParameterIn::Path && it.required == Some(true))
+    {
+        url = url.replace(
+            &format!("{{{}}}", parameter.name),
+            &placeholder(parameter, percent_encode),
+        );
+    }
+    let query: Vec<String> = parameters
+        .iter()
+        .filter(|it| it._in == ParameterIn::Query && it.required == Some(true))
+        .map(|it| {
+            format!(
+                "{}={}",
+                percent_encode(&it.name),
+                placeholder(it, percent_encode)
+            )
+        })
+        .collect();
+    if !query.is_empty() {
+        url = format!("{}?{}", url, query.join("&"));
+    }
+
+    let mut command = String::from("curl");
+    if method != HttpMethod::Get {
+        command.push_str(&format!(" -X {}", method.as_str().to_uppercase()));
+    }
+    command.push_str(&format!(" {}", quote(&url)));
+    for parameter in parameters
+        .iter()
+        .filter(|it| it._in == ParameterIn::Header && it.required == Some(true))
+    {
+        let header = format!(
+            "{}: {}",
+            parameter.name,
+            placeholder(parameter, str::to_owned)
+        );
+        command.push_str(&format!(" -H {}", quote(&header)));
+    }
+    let json_body = op
+        .request_body
+        .as_ref()
+        .and_then(|body| body.as_data())
+        .and_then(|body| body.content.get("application/json"));
+    if let Some(media_type) = json_body {
+        let body = media_type.example.clone().unwrap_or_else(|| {
+            media_type
+                .schema
+                .as_ref()
+                .and_then(|schema| schema.as_data())
+                .map(|schema| schema.example_value())
+                .unwrap_or(Any::Null)
+        });
+        command.push_str(&format!(
+            " -H {} -d {}",
+            quote("Content-Type: application/json"),
+            quote(&body.to_string())
+        ));
+    }
+    command
+}
+
+/// the example value of a parameter passed through `encode`, or `{name}`
+fn placeholder(parameter: &Parameter, encode: fn(&str) -> String) -> String {
+    let example = parameter.example.clone().or_else(|| {
+        parameter
+            .schema
+            .as_ref()
+            .and_then(|schema| schema.as_data())
+            .map(|schema| schema.example_value())
+            .filter(|value| !value.is_null())
+    });
+    match example {
+        Some(Any::String(value)) => encode(&value),
+        Some(value) => encode(&value.to_string()),
+        None => format!("{{{}}}", parameter.name),
+    }
+}
+
+/// percent-encode everything but the unreserved characters of RFC 3986
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// single-quote a shell argument
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// Render a Markdown reference of the whole document: the `info` section, a table of contents grouped by tag and a section per operation listing its parameters, request body and responses.
+///
+/// Path-level parameters are listed unless the operation overrides them by name and location. References to components are displayed by component name.
+pub fn to_markdown(spec: &OpenAPIV3) -> String {
+    let mut out = String::new();
+    let info = &spec.info;
+    writeln!(out, "# {}\n", info.title).unwrap();
+    writeln!(out, "Version: {}\n", info.version).unwrap();
+    if let Some(description) = &info.description {
+        writeln!(out, "{}\n", description).unwrap();
+    }
+
+    let operations: Vec<(&String, HttpMethod, &Operation)> = spec
+        .paths
+        .iter()
+        .flat_map(|(path, item)| {
+            item.operations()
+                .map(move |(method, operation)| (path, method, operation))
+        })
+        .collect();
+
+    let mut groups: Vec<(&str, Vec<usize>)> = vec![];
+    for (index, (_, _, operation)) in operations.iter().enumerate() {
+        for tag in operation_tags(operation) {
+            match groups.iter_mut().find(|(name, _)| *name == tag) {
+                Some((_, members)) => members.push(index),
+                None => groups.push((tag, vec![index])),
+            }
+        }
+    }
+    writeln!(out, "## Table of Contents\n").unwrap();
+    for (tag, members) in groups {
+        writeln!(out, "### {}\n", tag).unwrap();
+        for index in members {
+            let (path, method, operation) = operations[index];
+            let title = operation_title(path, method);
+            write!(out, "- [{}](#{})", title, anchor(&title)).unwrap();
+            if let Some(summary) = &operation.summary {
+                write!(out, " {}", summary).unwrap();
+            }
+            writeln!(out).unwrap();
+        }
+        writeln!(out).unwrap();
+    }
+
+    writeln!(out, "## Operations\n").unwrap();
+    for (path, method, operation) in operations {
+        write_operation(&mut out, spec, path, method, operation);
+    }
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+    out
+}
+
+/// the tags of an operation, `default` for an untagged one
+fn operation_tags(operation: &Operation) -> Vec<&str> {
+    let tags = operation.tags.as_deref().unwrap_or_default();
+    if tags.is_empty() {
+        vec!["default"]
+    } else {
+        tags.iter().map(String::as_str).collect()
+    }
+}
+
+/// Split a document into one document per tag.
+///
+/// Each document keeps `info`, `servers`, `security` and `externalDocs`, the declaration of its own tag, the paths holding at least one of its operations with only those operations, and the components they transitively reference. Untagged operations are grouped under `default` as in [`to_markdown`], and an operation with several tags appears in each of their documents.
+pub fn split_by_tag(spec: &OpenAPIV3) -> BTreeMap<String, OpenAPIV3> {
+    let template = OpenAPIV3 {
+        openapi: spec.openapi.clone(),
+        info: spec.info.clone(),
+        servers: spec.servers.clone(),
+        paths: Map::new(),
+        components: spec.components.clone(),
+        security: spec.security.clone(),
+        tags: None,
+        external_docs: spec.external_docs.clone(),
+        extras: spec.extras.clone(),
+        explicit_default_server: spec.explicit_default_server,
+    };
+    let mut documents: BTreeMap<String, OpenAPIV3> = BTreeMap::new();
+    for (path, item) in &spec.paths {
+        for (method, operation) in item.operations() {
+            for tag in operation_tags(operation) {
+                let document = documents.entry(tag.to_owned()).or_insert_with(|| {
+                    let declared: Vec<Tag> = spec
+                        .tags
+                        .iter()
+                        .flatten()
+                        .filter(|declared| declared.name == tag)
+                        .cloned()
+                        .collect();
+                    OpenAPIV3 {
+                        tags: Some(declared).filter(|tags| !tags.is_empty()),
+                        ..template.clone()
+                    }
+                });
+                let sliced = document.paths.entry(path.clone()).or_insert_with(|| {
+                    let mut sliced = item.clone();
+                    for method in HttpMethod::ALL {
+                        *sliced.operation_mut(method) = None;
+                    }
+                    sliced
+                });
+                *sliced.operation_mut(method) = Some(operation.clone());
+            }
+        }
+    }
+    for document in documents.values_mut() {
+        retain_used_components(document);
+    }
+    documents
+}
+
+/// drop every component the document does not use
+fn retain_used_components(document: &mut OpenAPIV3) {
+    fn retain<T>(map: &mut Option<Map<String, T>>, usage: KindUsage) {
+        if let Some(entries) = map {
+            entries.retain(|name, _| usage.referenced.contains(name));
+            if entries.is_empty() {
+                *map = None;
+            }
+        }
+    }
+    let usage = document.component_usage();
+    let Some(components) = &mut document.components else {
+        return;
+    };
+    retain(&mut components.schemas, usage.kind("schemas"));
+    retain(&mut components.responses, usage.kind("responses"));
+    retain(&mut components.parameters, usage.kind("parameters"));
+    retain(&mut components.examples, usage.kind("examples"));
+    retain(&mut components.request_bodies, usage.kind("requestBodies"));
+    retain(&mut components.headers, usage.kind("headers"));
+    retain(
+        &mut components.security_schemes,
+        usage.kind("securitySchemes"),
+    );
+    retain(&mut components.links, usage.kind("links"));
+    retain(&mut components.callbacks, usage.kind("callbacks"));
+    if components.is_empty() {
+        document.components = None;
+    }
+}
+
+fn write_operation(
+    out: &mut String,
+    spec: &OpenAPIV3,
+    path: &str,
+    method: HttpMethod,
+    operation: &Operation,
+) {
+    writeln!(out, "### {}\n", operation_title(path, method)).unwrap();
+    for text in [&operation.summary, &operation.description]
+        .into_iter()
+        .flatten()
+    {
+        writeln!(out, "{}\n", text).unwrap();
+    }
+
+    let empty = Components::default();
+    let components = spec.components.as_ref().unwrap_or(&empty);
+    let parameters = match spec.paths.get(path) {
+        Some(item) => item.effective_parameters(operation, components),
+        None => operation.parameters.clone().unwrap_or_default(),
+    };
+    let parameters: Vec<&Parameter> = parameters
+        .iter()
+        .filter_map(|parameter| parameter.resolve(components))
+        .collect();
+    if !parameters.is_empty() {
+        writeln!(out, "#### Parameters\n").unwrap();
+        writeln!(out, "| Name | In | Required | Type |").unwrap();
+        writeln!(out, "| --- | --- | --- | --- |").unwrap();
+        for parameter in parameters {
+            writeln!(
+                out,
+                "| {} | {} | {} | {} |",
+                cell(&parameter.name),
+                parameter._in.as_str(),
+                parameter.required.unwrap_or(false),
+                cell(
+                    &parameter
+                        .schema
+                        .as_ref()
+                        .map(schema_name)
+                        .unwrap_or_default()
+                )
+            )
+            .unwrap();
+        }
+        writeln!(out).unwrap();
+    }
+
+    if let Some(body) = operation.request_body.as_ref() {
+        writeln!(out, "#### Request Body\n").unwrap();
+        match spec.resolve(body) {
+            Some(body) => {
+                writeln!(out, "Required: {}\n", body.required.unwrap_or(false)).unwrap();
+                writeln!(out, "| Media Type | Schema |").unwrap();
+                writeln!(out, "| --- | --- |").unwrap();
+                for (media_type, content) in body.content.iter() {
+                    let schema = content.schema.as_ref().map(schema_name).unwrap_or_default();
+                    writeln!(out, "| {} | {} |", cell(media_type), cell(&schema)).unwrap();
+                }
+            }
+            None => writeln!(out, "{}", reference_name(body)).unwrap(),
+        }
+        writeln!(out).unwrap();
+    }
+
+    writeln!(out, "#### Responses\n").unwrap();
+    writeln!(out, "| Status | Description | Schema |").unwrap();
+    writeln!(out, "| --- | --- | --- |").unwrap();
+    for (status, response) in operation.responses.iter() {
+        match spec.resolve(response) {
+            Some(data) => {
+                let schemas: Vec<String> = data
+                    .content
+                    .iter()
+                    .flatten()
+                    .filter_map(|(_, content)| content.schema.as_ref().map(schema_name))
+                    .collect();
+                writeln!(
+                    out,
+                    "| {} | {} | {} |",
+                    cell(status),
+                    cell(&data.description),
+                    cell(&schemas.join(", "))
+                )
+                .unwrap();
+            }
+            None => writeln!(
+                out,
+                "| {} | {} | |",
+                cell(status),
+                cell(&reference_name(response))
+            )
+            .unwrap(),
+        }
+    }
+    writeln!(out).unwrap();
+}
+
+/// escape the text of a table cell, so pipes and line breaks do not end the cell or the row
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+fn operation_title(path: &str, method: HttpMethod) -> String {
+    format!("{} {}", method.as_str().to_uppercase(), path)
+}
+
+/// the GitHub flavoured heading anchor
+fn anchor(title: &str) -> String {
+    title
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+fn reference_name<T>(referenceable: &Referenceable<T>) -> String {
+    match referenceable {
+        Referenceable::Reference(reference) => reference
+            ._ref
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .to_owned(),
+        Referenceable::Data(_) => String::new(),
+    }
+}
+
+fn schema_name(schema: &Referenceable<Schema>) -> String {
+    match schema {
+        Referenceable::Reference(_) => reference_name(schema),
+        Referenceable::Data(schema) => match (schema._type.as_deref(), &schema.items) {
+            (Some("array"), Some(items)) => format!("{}[]", schema_name(items)),
+            (Some(_type), _) => match &schema.format {
+                Some(format) => format!("{} ({})", _type, format),
+                None => _type.to_owned(),
+            },
+            (None, _) if schema.properties.is_some() => "object".to_owned(),
+            (None, _) => String::new(),
+        },
+    }
+}
+
+impl Schema {
+    /// Emit this schema as a standalone JSON Schema document.
+    ///
+    /// References to `#/components/schemas/...` are rewritten to `#/$defs/...` and every transitively referenced component is bundled under `$defs`. `nullable: true` becomes a `type` union with `"null"`, `example` becomes a single-element `examples` array and boolean `exclusiveMinimum`/`exclusiveMaximum` become their numeric form.
+    pub fn to_json_schema(&self, components: &Components) -> Any {
+        let mut pending = vec![];
+        let mut root = self.to_value();
+        rewrite_json_schema(&mut root, Some(&mut pending));
+
+        let mut defs = serde_json::Map::new();
+        while let Some(name) = pending.pop() {
+            if defs.contains_key(&name) {
+                continue;
+            }
+            let mut definition = components
+                .schemas
+                .as_ref()
+                .and_then(|schemas| schemas.get(&name))
+                .map(|schema| serde_json::to_value(schema).unwrap())
+                .unwrap_or_else(|| Any::Object(Default::default()));
+            rewrite_json_schema(&mut definition, Some(&mut pending));
+            defs.insert(name, definition);
+        }
+        if !defs.is_empty() {
+            root["$defs"] = Any::Object(defs);
+        }
+        root
+    }
+}
+
+/// rewrite an OAS 3.0 schema into JSON Schema, moving component references to `$defs` when `referenced` collects their names
+fn rewrite_json_schema(value: &mut Any, mut referenced: Option<&mut Vec<String>>) {
+    let object = match value.as_object_mut() {
+        Some(object) => object,
+        None => return,
+    };
+    if let (Some(Any::String(reference)), Some(referenced)) =
+        (object.get_mut("$ref"), referenced.as_deref_mut())
+    {
+        if let Some(name) = reference.strip_prefix("#/components/schemas/") {
+            referenced.push(name.to_owned());
+            *reference = format!("#/$defs/{}", name);
+        }
+    }
+    if object.remove("nullable") == Some(Any::Bool(true)) {
+        if let Some(Any::String(_type)) = object.get("type") {
+            object["type"] = Any::Array(vec![Any::from(_type.as_str()), Any::from("null")]);
+        }
+    }
+    if let Some(example) = object.remove("example") {
+        object.insert("examples".to_owned(), Any::Array(vec![example]));
+    }
+    for (exclusive, bound) in [
+        ("exclusiveMinimum", "minimum"),
+        ("exclusiveMaximum", "maximum"),
+    ] {
+        if object.get(exclusive) == Some(&Any::Bool(true)) {
+            if let Some(limit) = object.remove(bound) {
+                object.insert(exclusive.to_owned(), limit);
+            }
+        } else if object.get(exclusive) == Some(&Any::Bool(false)) {
+            object.remove(exclusive);
+        }
+    }
+    for keyword in ["properties", "patternProperties"] {
+        if let Some(Any::Object(children)) = object.get_mut(keyword) {
+            children
+                .values_mut()
+                .for_each(|child| rewrite_json_schema(child, referenced.as_deref_mut()));
+        }
+    }
+    for keyword in ["items", "additionalProperties", "not"] {
+        if let Some(child) = object.get_mut(keyword) {
+            rewrite_json_schema(child, referenced.as_deref_mut());
+        }
+    }
+    for keyword in ["allOf", "oneOf", "anyOf"] {
+        if let Some(Any::Array(children)) = object.get_mut(keyword) {
+            children
+                .iter_mut()
+                .for_each(|child| rewrite_json_schema(child, referenced.as_deref_mut()));
+        }
+    }
+}
+
+impl OpenAPIV3 {
+    /// Rewrite this document as OpenAPI 3.1 JSON.
+    ///
+    /// `openapi` becomes `3.1.0` and every schema is rewritten as JSON Schema: `nullable: true` becomes a `type` union with `"null"`, `example` becomes an `examples` array and boolean `exclusiveMinimum`/`exclusiveMaximum` become their numeric form.
+    ///
+    /// Not supported:
+    /// - `nullable` on a schema without a `type`, e.g. next to `allOf` or `$ref`, is dropped rather than rewritten to a `oneOf` with `{"type": "null"}`
+    /// - `example` on parameters and media types is kept as is, which 3.1 still allows
+    /// - 3.1 only features such as `webhooks`, `license.identifier` or `pathItems` components are never produced
+    pub fn to_3_1_json(&self) -> Any {
+        let mut document = self.to_value();
+        document["openapi"] = Any::from("3.1.0");
+        rewrite_schemas_3_1(&mut document);
+        document
+    }
+}
+
+fn rewrite_schemas_3_1(value: &mut Any) {
+    match value {
+        Any::Object(object) => {
+            for (key, child) in object.iter_mut() {
+                match key.as_str() {
+                    "schema" => rewrite_json_schema(child, None),
+                    "schemas" => {
+                        if let Some(schemas) = child.as_object_mut() {
+                            schemas
+                                .values_mut()
+                                .for_each(|schema| rewrite_json_schema(schema, None));
+                        }
+                    }
+                    // user values of parameters and media types; schema keywords such as `default` never reach here
+                    "example" | "examples" => {}
+                    key if key.starts_with("x-") => {}
+                    _ => rewrite_schemas_3_1(child),
+                }
+            }
+        }
+        Any::Array(elements) => elements.iter_mut().for_each(rewrite_schemas_3_1),
+        _ => {}
+    }
+}
+
+/// Render a component schema as a TypeScript declaration, e.g. `export interface User { ... }`.
+///
+/// Properties missing from `required` are optional (`?`), `integer` and `number` map to `number`, arrays to `T[]`, string enums to a union of literals and `nullable` adds `| null`. References are written as the referenced interface name, or `unknown` when `components` has no such schema. A schema without properties is emitted as a type alias instead.
+pub fn to_typescript_interface(name: &str, schema: &Schema, components: &Components) -> String {
+    if schema.properties.is_none() {
+        return format!("export type {} = {};\n", name, ts_type(schema, components));
+    }
+    let mut out = format!("export interface {} {{\n", name);
+    for (property, optional, schema) in ts_properties(schema) {
+        writeln!(
+            out,
+            "  {}{}: {};",
+            ts_property_name(property),
+            if optional { "?" } else { "" },
+            ts_reference(schema, components)
+        )
+        .unwrap();
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// each property with whether it is optional
+fn ts_properties(schema: &Schema) -> impl Iterator<Item = (&str, bool, &Referenceable<Schema>)> {
+    let required = schema.required.as_deref().unwrap_or_default();
+    schema
+        .properties
+        .iter()
+        .flatten()
+        .map(move |(name, property)| (name.as_str(), !required.contains(name), property))
+}
+
+fn ts_property_name(name: &str) -> String {
+    let is_identifier = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if is_identifier {
+        name.to_owned()
+    } else {
+        Any::from(name).to_string()
+    }
+}
+
+fn ts_reference(schema: &Referenceable<Schema>, components: &Components) -> String {
+    match schema {
+        Referenceable::Reference(reference) => {
+            match reference._ref.strip_prefix("#/components/schemas/") {
+                Some(name)
+                    if components
+                        .schemas
+                        .as_ref()
+                        .is_some_and(|schemas| schemas.contains_key(name)) =>
+                {
+                    name.to_owned()
+                }
+                _ => "unknown".to_owned(),
+            }
+        }
+        Referenceable::Data(schema) => ts_type(schema, components),
+    }
+}
+
+fn ts_type(schema: &Schema, components: &Components) -> String {
+    let strings: Option<Vec<String>> = schema._enum.as_ref().and_then(|values| {
+        values
+            .iter()
+            .map(|it| it.as_str().map(|it| Any::from(it).to_string()))
+            .collect()
+    });
+    let _type = match (schema._type.as_deref(), strings) {
+        (Some("string"), Some(literals)) if !literals.is_empty() => literals.join(" | "),
+        (Some("string"), _) => "string".to_owned(),
+        (Some("integer" | "number"), _) => "number".to_owned(),
+        (Some("boolean"), _) => "boolean".to_owned(),
+        (Some("array"), _) => {
+            let items = schema
+                .items
+                .as_ref()
+                .map(|items| ts_reference(items, components))
+                .unwrap_or_else(|| "unknown".to_owned());
+            if items.contains(' ') {
+                format!("({})[]", items)
+            } else {
+                format!("{}[]", items)
+            }
+        }
+        _ if schema.properties.is_some() => {
+            let properties: Vec<String> = ts_properties(schema)
+                .map(|(property, optional, schema)| {
+                    format!(
+                        "{}{}: {}",
+                        ts_property_name(property),
+                        if optional { "?" } else { "" },
+                        ts_reference(schema, components)
+                    )
+                })
+                .collect();
+            format!("{{ {} }}", properties.join("; "))
+        }
+        (Some("object"), _) => "Record<string, unknown>".to_owned(),
+        _ => "unknown".to_owned(),
+    };
+    if schema.nullable == Some(true) {
+        format!("{} | null", _type)
+    } else {
+        _type
+    }
+}
+
+/// Render a component schema as a Rust struct deriving `Serialize` and `Deserialize`.
+///
+/// Properties missing from `required` or `nullable` become `Option<T>`, arrays `Vec<T>` and references the referenced struct name, boxed when the schema refers to itself. `integer` maps to `i32` for `format: int32` and `i64` otherwise, `number` to `f32` for `format: float` and `f64` otherwise. Strings of every format, `date-time` included, stay `String`. Inline objects and references missing from `components` become `serde_json::Value`. Property names are converted to snake_case with a `serde(rename)` when they differ, and a field name already taken by another property, such as `foo_bar` next to `fooBar`, gets a numeric suffix. A schema without properties is emitted as a type alias instead.
+pub fn to_rust_struct(name: &str, schema: &Schema, components: &Components) -> String {
+    if schema.properties.is_none() {
+        return format!(
+            "pub type {} = {};\n",
+            name,
+            rust_type(name, schema, components)
+        );
+    }
+    let mut out = format!(
+        "#[derive(Serialize, Deserialize)]\npub struct {} {{\n",
+        name
+    );
+    let required = schema.required.as_deref().unwrap_or_default();
+    let properties = schema.properties.iter().flatten();
+    // properties already spelled as their field keep it, the others give way
+    let mut taken: Vec<String> = properties
+        .clone()
+        .filter(|(property, _)| rust_field_name(property).trim_start_matches("r#") == *property)
+        .map(|(property, _)| property.clone())
+        .collect();
+    for (property, schema) in properties {
+        let mut field = rust_field_name(property);
+        let bare = field.trim_start_matches("r#").to_owned();
+        if bare != *property && taken.contains(&bare) {
+            field = unique_name(bare, |name| taken.iter().any(|taken| taken == name));
+        }
+        taken.push(field.trim_start_matches("r#").to_owned());
+        if field.trim_start_matches("r#") != property {
+            writeln!(
+                out,
+                "    #[serde(rename = {})]",
+                Any::from(property.as_str())
+            )
+            .unwrap();
+        }
+        let mut _type = rust_reference(name, schema, components);
+        if _type == name {
+            _type = format!("Box<{}>", _type);
+        }
+        if !required.contains(property) && !_type.starts_with("Option<") {
+            _type = format!("Option<{}>", _type);
+        }
+        writeln!(out, "    pub {}: {},", field, _type).unwrap();
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn rust_field_name(name: &str) -> String {
+    let mut field = String::new();
+    let mut previous: Option<char> = None;
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            if previous.is_some_and(|p| p.is_ascii_lowercase() || p.is_ascii_digit()) {
+                field.push('_');
+            }
+            field.push(c.to_ascii_lowercase());
+        } else if c.is_ascii_alphanumeric() {
+            field.push(c);
+        } else if !field.ends_with('_') {
+            field.push('_');
+        }
+        previous = Some(c);
+    }
+    if field.is_empty() || field.starts_with(|c: char| c.is_ascii_digit()) {
+        field.insert(0, '_');
+    }
+    match field.as_str() {
+        "self" | "super" | "crate" => format!("{}_", field),
+        "as" | "async" | "await" | "break" | "const" | "continue" | "dyn" | "else" | "enum"
+        | "extern" | "false" | "fn" | "for" | "if" | "impl" | "in" | "let" | "loop" | "match"
+        | "mod" | "move" | "mut" | "pub" | "ref" | "return" | "static" | "struct" | "trait"
+        | "true" | "type" | "unsafe" | "use" | "where" | "while" => format!("r#{}", field),
+        _ => field,
+    }
+}
+
+/// `owner` is the name of the struct being generated, used to spot self references
+fn rust_reference(owner: &str, schema: &Referenceable<Schema>, components: &Components) -> String {
+    match schema {
+        Referenceable::Reference(reference) => {
+            match reference._ref.strip_prefix("#/components/schemas/") {
+                Some(name)
+                    if components
+                        .schemas
+                        .as_ref()
+                        .is_some_and(|schemas| schemas.contains_key(name)) =>
+                {
+                    name.to_owned()
+                }
+                _ => "serde_json::Value".to_owned(),
+            }
+        }
+        Referenceable::Data(schema) => rust_type(owner, schema, components),
+    }
+}
+
+fn rust_type(owner: &str, schema: &Schema, components: &Components) -> String {
+    let _type = match (schema._type.as_deref(), schema.format.as_deref()) {
+        (Some("string"), _) => "String".to_owned(),
+        (Some("integer"), Some("int32")) => "i32".to_owned(),
+        (Some("integer"), _) => "i64".to_owned(),
+        (Some("number"), Some("float")) => "f32".to_owned(),
+        (Some("number"), _) => "f64".to_owned(),
+        (Some("boolean"), _) => "bool".to_owned(),
+        (Some("array"), _) => {
+            let items = schema
+                .items
+                .as_ref()
+                .map(|items| rust_reference(owner, items, components))
+                .unwrap_or_else(|| "serde_json::Value".to_owned());
+            format!("Vec<{}>", items)
+        }
+        _ => "serde_json::Value".to_owned(),
+    };
+    if schema.nullable == Some(true) {
+        format!("Option<{}>", _type)
+    } else {
+        _type
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{curl_example, split_by_tag, to_markdown, to_rust_struct, to_typescript_interface};
+    use crate::{Components, HttpMethod, OpenAPIV3, Operation, Schema, Server};
+    use serde_json::json;
+
+    fn server() -> Server {
+        serde_json::from_value(json!({"url": "https://petstore.swagger.io/v1/"})).unwrap()
+    }
+
+    #[test]
+    fn should_render_get_with_path_parameter() {
+        let operation: Operation = serde_json::from_value(json!({
+            "parameters": [
+                {"name": "petId", "in": "path", "required": true, "schema": {"type": "integer"}, "example": 42},
+                {"name": "fields", "in": "query", "required": true, "schema": {"type": "string", "example": "name"}},
+                {"name": "q", "in": "query", "required": true, "example": "cats & dogs"},
+                {"name": "verbose", "in": "query", "schema": {"type": "boolean"}},
+                {"name": "X-Tenant", "in": "header", "required": true, "example": "acme corp"},
+                {"name": "X-Request-Id", "in": "header", "schema": {"type": "string"}}
+            ],
+            "responses": {"200": {"description": "a pet"}}
+        }))
+        .unwrap();
+        assert_eq!(
+            curl_example(&server(), "/pets/{petId}", HttpMethod::Get, &operation),
+            "curl 'https://petstore.swagger.io/v1/pets/42?fields=name&q=cats%20%26%20dogs' -H 'X-Tenant: acme corp'"
+        );
+    }
+
+    #[test]
+    fn should_render_post_with_json_body() {
+        let operation: Operation = serde_json::from_value(json!({
+            "requestBody": {
+                "content": {
+                    "application/json": {
+                        "schema": {
+                            "type": "object",
+                            "properties": {
+                                "name": {"type": "string", "example": "O'Malley"},
+                                "age": {"type": "integer"}
+                            }
+                        }
+                    }
+                }
+            },
+            "responses": {"201": {"description": "created"}}
+        }))
+        .unwrap();
+        assert_eq!(
+            curl_example(&server(), "/pets", HttpMethod::Post, &operation),
+            r#"curl -X POST 'https://petstore.swagger.io/v1/pets' -H 'Content-Type: application/json' -d '{"age":0,"name":"O'\''Malley"}'"#
+        );
+    }
+
+    #[test]
+    fn should_render_petstore_markdown() {
+        let spec: OpenAPIV3 =
+            serde_json::from_str(include_str!("../examples/v3.0/json/petstore.json")).unwrap();
+        let expected = r#"# Swagger Petstore
+
+Version: 1.0.0
+
+## Table of Contents
+
+### pets
+
+- [GET /pets](#get-pets) List all pets
+- [POST /pets](#post-pets) Create a pet
+- [GET /pets/{petId}](#get-petspetid) Info for a specific pet
+
+## Operations
+
+### GET /pets
+
+List all pets
+
+#### Parameters
+
+| Name | In | Required | Type |
+| --- | --- | --- | --- |
+| limit | query | false | integer (int32) |
+
+#### Responses
+
+| Status | Description | Schema |
+| --- | --- | --- |
+| 200 | An paged array of pets | Pets |
+| default | unexpected error | Error |
+
+### POST /pets
+
+Create a pet
+
+#### Responses
+
+| Status | Description | Schema |
+| --- | --- | --- |
+| 201 | Null response |  |
+| default | unexpected error | Error |
+
+### GET /pets/{petId}
+
+Info for a specific pet
+
+#### Parameters
+
+| Name | In | Required | Type |
+| --- | --- | --- | --- |
+| petId | path | true | string |
+
+#### Responses
+
+| Status | Description | Schema |
+| --- | --- | --- |
+| 200 | Expected response to a valid request | Pets |
+| default | unexpected error | Error |
+"#;
+        assert_eq!(to_markdown(&spec), expected);
+    }
+
+    #[test]
+    fn should_render_overridden_parameters_and_escape_cells() {
+        let spec: OpenAPIV3 = serde_json::from_value(json!({
+            "openapi": "3.0.3",
+            "info": {"title": "pets", "version": "1.0"},
+            "paths": {
+                "/pets": {
+                    "parameters": [
+                        {"name": "limit", "in": "query", "schema": {"type": "string"}},
+                        {"name": "X-Tenant", "in": "header", "schema": {"type": "string"}}
+                    ],
+                    "get": {
+                        "parameters": [{"name": "limit", "in": "query", "required": true, "schema": {"type": "integer"}}],
+                        "responses": {"200": {"description": "cats | dogs\nor birds"}}
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let markdown = to_markdown(&spec);
+        assert!(markdown.contains(
+            "| X-Tenant | header | false | string |\n| limit | query | true | integer |\n\n"
+        ));
+        assert!(!markdown.contains("| limit | query | false | string |"));
+        assert!(markdown.contains("| 200 | cats \\| dogs<br>or birds |  |"));
+    }
+
+    #[test]
+    fn should_convert_schema_to_json_schema() {
+        let components: Components = serde_json::from_value(json!({
+            "schemas": {
+                "User": {
+                    "type": "object",
+                    "required": ["id"],
+                    "properties": {
+                        "id": {"type": "integer", "minimum": 0, "exclusiveMinimum": true},
+                        "nickname": {"type": "string", "nullable": true, "example": "kil"},
+                        "address": {"$ref": "#/components/schemas/Address"}
+                    }
+                },
+                "Address": {
+                    "type": "object",
+                    "properties": {"country": {"$ref": "#/components/schemas/Country"}}
+                },
+                "Country": {"type": "string", "enum": ["CN", "US"]},
+                "Unused": {"type": "string"}
+            }
+        }))
+        .unwrap();
+        let user = components.schemas.as_ref().unwrap()["User"]
+            .as_data()
+            .unwrap();
+        assert_eq!(
+            user.to_json_schema(&components),
+            json!({
+                "type": "object",
+                "required": ["id"],
+                "properties": {
+                    "id": {"type": "integer", "exclusiveMinimum": 0},
+                    "nickname": {"type": ["string", "null"], "examples": ["kil"]},
+                    "address": {"$ref": "#/$defs/Address"}
+                },
+                "$defs": {
+                    "Address": {
+                        "type": "object",
+                        "properties": {"country": {"$ref": "#/$defs/Country"}}
+                    },
+                    "Country": {"type": "string", "enum": ["CN", "US"]}
+                }
+            })
+        );
+        assert_eq!(Schema::default().to_json_schema(&components), json!({}));
+    }
+
+    #[test]
+    fn should_upgrade_document_to_3_1() {
+        let spec: OpenAPIV3 = serde_json::from_value(json!({
+            "openapi": "3.0.3",
+            "info": {"title": "users", "version": "1.0.0"},
+            "paths": {
+                "/users": {
+                    "get": {
+                        "parameters": [{
+                            "name": "nickname",
+                            "in": "query",
+                            "schema": {"type": "string", "nullable": true}
+                        }],
+                        "responses": {
+                            "200": {
+                                "description": "users",
+                                "content": {"application/json": {
+                                    "schema": {"type": "array", "items": {"$ref": "#/components/schemas/User"}},
+                                    "example": [{"nullable": true}]
+                                }}
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "User": {
+                        "type": "object",
+                        "properties": {"age": {"type": "integer", "nullable": true, "example": 18}}
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let upgraded = spec.to_3_1_json();
+        assert_eq!(upgraded["openapi"], "3.1.0");
+        let parameter = &upgraded["paths"]["/users"]["get"]["parameters"][0];
+        assert_eq!(parameter["schema"], json!({"type": ["string", "null"]}));
+        assert_eq!(
+            upgraded["components"]["schemas"]["User"]["properties"]["age"],
+            json!({"type": ["integer", "null"], "examples": [18]})
+        );
+        let content =
+            &upgraded["paths"]["/users"]["get"]["responses"]["200"]["content"]["application/json"];
+        assert_eq!(
+            content["schema"]["items"]["$ref"],
+            "#/components/schemas/User"
+        );
+        assert_eq!(content["example"], json!([{"nullable": true}]));
+    }
+
+    #[test]
+    fn should_upgrade_default_response_to_3_1() {
+        let spec: OpenAPIV3 = serde_json::from_value(json!({
+            "openapi": "3.0.3",
+            "info": {"title": "users", "version": "1.0.0"},
+            "paths": {
+                "/users": {
+                    "get": {
+                        "responses": {
+                            "default": {
+                                "description": "error",
+                                "content": {"application/json": {
+                                    "schema": {"type": "string", "nullable": true}
+                                }}
+                            }
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let upgraded = spec.to_3_1_json();
+        assert_eq!(
+            upgraded["paths"]["/users"]["get"]["responses"]["default"]["content"]
+                ["application/json"]["schema"],
+            json!({"type": ["string", "null"]})
+        );
+    }
+
+    #[test]
+    fn should_generate_typescript_interface() {
+        let components: Components = serde_json::from_value(json!({
+            "schemas": {
+                "User": {
+                    "type": "object",
+                    "required": ["id", "name", "tags"],
+                    "properties": {
+                        "id": {"type": "integer", "format": "int64"},
+                        "name": {"type": "string"},
+                        "nickname": {"type": "string", "nullable": true},
+                        "admin": {"type": "boolean"},
+                        "tags": {"type": "array", "items": {"type": "string"}},
+                        "role": {"type": "string", "enum": ["owner", "member"]},
+                        "address": {"$ref": "#/components/schemas/Address"},
+                        "created-at": {"type": "string", "format": "date-time"},
+                        "location": {
+                            "type": "object",
+                            "required": ["lat"],
+                            "properties": {"lat": {"type": "number"}, "lng": {"type": "number"}}
+                        }
+                    }
+                },
+                "Address": {"type": "object", "properties": {"city": {"type": "string"}}},
+                "Ids": {"type": "array", "items": {"type": "integer", "nullable": true}}
+            }
+        }))
+        .unwrap();
+        let schemas = components.schemas.as_ref().unwrap();
+        let user = schemas["User"].as_data().unwrap();
+        assert_eq!(
+            to_typescript_interface("User", user, &components),
+            r#"export interface User {
+  address?: Address;
+  admin?: boolean;
+  "created-at"?: string;
+  id: number;
+  location?: { lat: number; lng?: number };
+  name: string;
+  nickname?: string | null;
+  role?: "owner" | "member";
+  tags: string[];
+}
+"#
+        );
+        let ids = schemas["Ids"].as_data().unwrap();
+        assert_eq!(
+            to_typescript_interface("Ids", ids, &components),
+            "export type Ids = (number | null)[];\n"
+        );
+    }
+
+    #[test]
+    fn should_generate_rust_struct() {
+        let components: Components = serde_json::from_value(json!({
+            "schemas": {
+                "User": {
+                    "type": "object",
+                    "required": ["id", "type", "friends"],
+                    "properties": {
+                        "id": {"type": "integer", "format": "int64"},
+                        "age": {"type": "integer", "format": "int32"},
+                        "type": {"type": "string"},
+                        "createdAt": {"type": "string", "format": "date-time"},
+                        "nickname": {"type": "string", "nullable": true},
+                        "address": {"$ref": "#/components/schemas/Address"},
+                        "friends": {"type": "array", "items": {"$ref": "#/components/schemas/User"}},
+                        "manager": {"$ref": "#/components/schemas/User"}
+                    }
+                },
+                "Address": {"type": "object", "properties": {"city": {"type": "string"}}}
+            }
+        }))
+        .unwrap();
+        let user = components.schemas.as_ref().unwrap()["User"]
+            .as_data()
+            .unwrap();
+        assert_eq!(
+            to_rust_struct("User", user, &components),
+            r#"#[derive(Serialize, Deserialize)]
+pub struct User {
+    pub address: Option<Address>,
+    pub age: Option<i32>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<String>,
+    pub friends: Vec<User>,
+    pub id: i64,
+    pub manager: Option<Box<User>>,
+    pub nickname: Option<String>,
+    pub r#type: String,
+}
+"#
+        );
+
+        let colliding: Schema = serde_json::from_value(json!({
+            "type": "object",
+            "properties": {"fooBar": {"type": "string"}, "foo_bar": {"type": "integer"}}
+        }))
+        .unwrap();
+        assert_eq!(
+            to_rust_struct("Colliding", &colliding, &components),
+            r#"#[derive(Serialize, Deserialize)]
+pub struct Colliding {
+    #[serde(rename = "fooBar")]
+    pub foo_bar2: Option<String>,
+    pub foo_bar: Option<i64>,
+}
+"#
+        );
+    }
+
+    #[test]
+    fn should_split_by_tag() {
+        let spec: OpenAPIV3 = serde_json::from_value(json!({
+            "openapi": "3.0.3",
+            "info": {"title": "shop", "version": "1.0.0"},
+            "servers": [{"url": "https://shop.example.com"}],
+            "tags": [{"name": "users"}, {"name": "orders"}],
+            "paths": {
+                "/users": {
+                    "get": {
+                        "tags": ["users"],
+                        "responses": {"200": {"description": "users", "content": {"application/json": {
+                            "schema": {"type": "array", "items": {"$ref": "#/components/schemas/User"}}
+                        }}}}
+                    }
+                },
+                "/orders": {
+                    "get": {
+                        "tags": ["orders"],
+                        "responses": {"200": {"description": "orders", "content": {"application/json": {
+                            "schema": {"$ref": "#/components/schemas/Order"}
+                        }}}}
+                    },
+                    "post": {
+                        "tags": ["orders"],
+                        "responses": {"default": {"$ref": "#/components/responses/Error"}}
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "User": {"type": "object", "properties": {"id": {"type": "integer"}}},
+                    "Order": {"type": "object", "properties": {"buyer": {"$ref": "#/components/schemas/User"}}}
+                },
+                "responses": {"Error": {"description": "failure"}}
+            }
+        }))
+        .unwrap();
+
+        let documents = split_by_tag(&spec);
+        assert_eq!(
+            documents.keys().collect::<Vec<_>>(),
+            vec!["orders", "users"]
+        );
+
+        let users = &documents["users"];
+        assert_eq!(users.paths.keys().collect::<Vec<_>>(), vec!["/users"]);
+        assert_eq!(users.servers, spec.servers);
+        assert_eq!(users.tags.as_ref().unwrap()[0].name, "users");
+        let components = users.components.as_ref().unwrap();
+        assert_eq!(
+            components
+                .schemas
+                .as_ref()
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["User"]
+        );
+        assert!(components.responses.is_none());
+
+        let orders = &documents["orders"];
+        assert_eq!(orders.paths.keys().collect::<Vec<_>>(), vec!["/orders"]);
+        assert_eq!(orders.operations().count(), 2);
+        let components = orders.components.as_ref().unwrap();
+        assert_eq!(components.schemas.as_ref().unwrap().len(), 2);
+        assert!(components.responses.as_ref().unwrap().contains_key("Error"));
+    }
+}
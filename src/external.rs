@@ -0,0 +1,401 @@
+//! Inlining references to other local files, e.g. `./schemas/user.yaml#/User`.
+
+use crate::refs::WalkRefs;
+use crate::transform::{escape, KINDS};
+use crate::{Any, Components, Map, OpenAPIV3, Referenceable, ResolveError};
+use serde::de::DeserializeOwned;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// A referenced value waiting to be added to `components`.
+struct Pending {
+    kind: &'static str,
+    name: String,
+    value: Any,
+}
+
+/// Shared state of one resolution: file contents and the component given to each target.
+#[derive(Default)]
+struct Resolver {
+    files: BTreeMap<PathBuf, Any>,
+    names: BTreeMap<(&'static str, String), String>,
+    taken: BTreeSet<(&'static str, String)>,
+    pending: Vec<Pending>,
+}
+
+impl OpenAPIV3 {
+    /// Inline every reference to another local JSON or YAML file into `components`, rewriting it to the local `#/components/{kind}/{name}` form.
+    ///
+    /// Relative paths are resolved against `base_dir`, and against the referencing file for references found in referenced files. This is also how a document split over several files is bundled into one: each target becomes one component named after the last segment of its pointer, or the file name for whole-file references, and every reference to it is rewritten to that name rather than inlined. When two files define the same name, the target reached first keeps it and later ones get a numeric suffix, e.g. `Address2`. Recursive structures spanning files become recursive component references; references that only lead to each other are reported as a cycle. Path item `$ref`s and references to URLs such as `https://example.com/pet.json` are left untouched.
+    pub fn resolve_external(&self, base_dir: &Path) -> Result<OpenAPIV3, ResolveError> {
+        let mut spec = self.clone();
+        let mut resolver = Resolver::default();
+        if let Some(components) = &spec.components {
+            for kind in KINDS {
+                for name in component_names(components, kind) {
+                    resolver.taken.insert((kind, name));
+                }
+            }
+        }
+
+        // inlined components may reference further files, so walk until nothing new shows up
+        loop {
+            let mut error = None;
+            spec.walk_refs(&mut |kind, reference| {
+                if error.is_none() {
+                    if let Err(e) = resolver.rewrite(kind, reference, base_dir) {
+                        error = Some(e);
+                    }
+                }
+            });
+            if let Some(error) = error {
+                return Err(error);
+            }
+            if resolver.pending.is_empty() {
+                return Ok(spec);
+            }
+            let components = spec.components.get_or_insert_with(Components::new);
+            for Pending { kind, name, value } in std::mem::take(&mut resolver.pending) {
+                insert(components, kind, name.clone(), value).map_err(|message| {
+                    ResolveError::Invalid {
+                        reference: format!("#/components/{}/{}", kind, escape(&name)),
+                        message,
+                    }
+                })?;
+            }
+        }
+    }
+}
+
+impl Resolver {
+    /// Rewrite an external reference to its component, loading the target when first seen.
+    ///
+    /// Local references are left alone, as are references whose kind is unknown.
+    fn rewrite(
+        &mut self,
+        kind: Option<&'static str>,
+        reference: &mut String,
+        base_dir: &Path,
+    ) -> Result<(), ResolveError> {
+        let Some(kind) = kind else { return Ok(()) };
+        if reference.starts_with('#') || is_remote(reference) {
+            return Ok(());
+        }
+        let (path, pointer) = self.target(reference, base_dir)?;
+        let key = (kind, format!("{}#{}", path.display(), pointer));
+        if let Some(name) = self.names.get(&key) {
+            *reference = format!("#/components/{}/{}", kind, escape(name));
+            return Ok(());
+        }
+
+        let mut value = self.follow(reference, path.clone(), pointer.clone())?;
+        // references local to the referenced file now have to name that file
+        let dir = path.parent().unwrap_or(Path::new("")).to_path_buf();
+        value.walk_refs(&mut |_, nested| {
+            *nested = match nested.strip_prefix('#') {
+                Some(fragment) => format!("{}#{}", path.display(), fragment),
+                None => absolute(&dir, nested),
+            };
+        });
+
+        let stem = pointer
+            .rsplit('/')
+            .next()
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+            .or_else(|| {
+                let stem = path.file_stem()?;
+                Some(stem.to_string_lossy().into_owned())
+            })
+            .unwrap_or_else(|| kind.to_string());
+        let name = (1..)
+            .map(|suffix| match suffix {
+                1 => stem.clone(),
+                suffix => format!("{}{}", stem, suffix),
+            })
+            .find(|name| !self.taken.contains(&(kind, name.clone())))
+            .expect("an unused suffix always exists");
+        self.taken.insert((kind, name.clone()));
+        self.names.insert(key, name.clone());
+        self.pending.push(Pending {
+            kind,
+            name: name.clone(),
+            value,
+        });
+        *reference = format!("#/components/{}/{}", kind, escape(&name));
+        Ok(())
+    }
+
+    /// The canonical file and pointer a reference points at.
+    fn target(&self, reference: &str, base_dir: &Path) -> Result<(PathBuf, String), ResolveError> {
+        let (file, pointer) = reference.split_once('#').unwrap_or((reference, ""));
+        let joined = base_dir.join(file);
+        let path = joined.canonicalize().map_err(|_| ResolveError::Missing {
+            reference: reference.to_string(),
+            path: joined,
+        })?;
+        Ok((path, pointer.to_string()))
+    }
+
+    /// Load the target of a reference, following references that point straight at other references.
+    fn follow(
+        &mut self,
+        reference: &str,
+        path: PathBuf,
+        pointer: String,
+    ) -> Result<Any, ResolveError> {
+        let mut chain = vec![reference.to_string()];
+        let (mut path, mut pointer) = (path, pointer);
+        loop {
+            let value = self
+                .load(&path)?
+                .pointer(&pointer)
+                .cloned()
+                .ok_or_else(|| ResolveError::NotFound(chain.last().cloned().unwrap_or_default()))?;
+            let next = match value.as_object().and_then(|object| object.get("$ref")) {
+                Some(Any::String(next))
+                    if value.as_object().is_some_and(|object| object.len() == 1) =>
+                {
+                    next.clone()
+                }
+                _ => return Ok(value),
+            };
+            if is_remote(&next) {
+                return Ok(value);
+            }
+            let dir = path.parent().unwrap_or(Path::new("")).to_path_buf();
+            let next = match next.strip_prefix('#') {
+                Some(fragment) => format!("{}#{}", path.display(), fragment),
+                None => absolute(&dir, &next),
+            };
+            if chain.contains(&next) {
+                chain.push(next);
+                return Err(ResolveError::Cycle(chain));
+            }
+            (path, pointer) = self.target(&next, Path::new(""))?;
+            chain.push(next);
+        }
+    }
+
+    fn load(&mut self, path: &Path) -> Result<&Any, ResolveError> {
+        if !self.files.contains_key(path) {
+            let text = std::fs::read_to_string(path).map_err(|_| ResolveError::Missing {
+                reference: path.display().to_string(),
+                path: path.to_path_buf(),
+            })?;
+            let yaml = matches!(
+                path.extension().and_then(|extension| extension.to_str()),
+                Some("yaml" | "yml")
+            );
+            let value = if yaml {
+                serde_yaml::from_str(&text).map_err(|e| e.to_string())
+            } else {
+                serde_json::from_str(&text).map_err(|e| e.to_string())
+            }
+            .map_err(|message| ResolveError::Parse {
+                path: path.to_path_buf(),
+                message,
+            })?;
+            self.files.insert(path.to_path_buf(), value);
+        }
+        Ok(&self.files[path])
+    }
+}
+
+/// Whether a reference names a URL with a scheme, e.g. `https://example.com/pet.json`, rather than a local file.
+///
+/// Such references are left as they are; a single letter before `:` is taken for a Windows drive.
+fn is_remote(reference: &str) -> bool {
+    reference.split_once(':').is_some_and(|(scheme, _)| {
+        scheme.len() > 1
+            && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+    })
+}
+
+/// Make a relative file reference absolute by joining it onto `dir`.
+fn absolute(dir: &Path, reference: &str) -> String {
+    if reference.starts_with('#') || is_remote(reference) {
+        return reference.to_string();
+    }
+    dir.join(reference).display().to_string()
+}
+
+fn component_names(components: &Components, kind: &str) -> Vec<String> {
+    fn names<T>(map: &Option<Map<String, T>>) -> Vec<String> {
+        map.iter().flat_map(Map::keys).cloned().collect()
+    }
+    match kind {
+        "schemas" => names(&components.schemas),
+        "responses" => names(&components.responses),
+        "parameters" => names(&components.parameters),
+        "examples" => names(&components.examples),
+        "requestBodies" => names(&components.request_bodies),
+        "headers" => names(&components.headers),
+        "securitySchemes" => names(&components.security_schemes),
+        "links" => names(&components.links),
+        "callbacks" => names(&components.callbacks),
+        _ => vec![],
+    }
+}
+
+/// Add an untyped component to the map of its kind.
+fn insert(components: &mut Components, kind: &str, name: String, value: Any) -> Result<(), String> {
+    fn insert_into<T: DeserializeOwned>(
+        map: &mut Option<Map<String, Referenceable<T>>>,
+        name: String,
+        value: Any,
+    ) -> Result<(), String> {
+        let value = serde_json::from_value(value).map_err(|e| e.to_string())?;
+        map.get_or_insert_with(Map::new).insert(name, value);
+        Ok(())
+    }
+    match kind {
+        "schemas" => insert_into(&mut components.schemas, name, value),
+        "responses" => insert_into(&mut components.responses, name, value),
+        "parameters" => insert_into(&mut components.parameters, name, value),
+        "examples" => insert_into(&mut components.examples, name, value),
+        "requestBodies" => insert_into(&mut components.request_bodies, name, value),
+        "headers" => insert_into(&mut components.headers, name, value),
+        "securitySchemes" => insert_into(&mut components.security_schemes, name, value),
+        "links" => insert_into(&mut components.links, name, value),
+        "callbacks" => insert_into(&mut components.callbacks, name, value),
+        _ => Err(format!("unknown component kind `{}`", kind)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Schema;
+
+    fn fixture() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/v3.0/external")
+    }
+
+    #[test]
+    fn should_inline_referenced_files() {
+        let spec: OpenAPIV3 =
+            serde_json::from_str(include_str!("../examples/v3.0/external/openapi.json")).unwrap();
+        let resolved = spec.resolve_external(&fixture()).unwrap();
+
+        let json = resolved.to_string();
+        assert!(!json.contains(".yaml"), "{}", json);
+        let schemas = resolved
+            .components
+            .as_ref()
+            .unwrap()
+            .schemas
+            .as_ref()
+            .unwrap();
+        let mut names: Vec<&String> = schemas.keys().collect();
+        names.sort();
+        assert_eq!(names, vec!["Address", "Error", "User"]);
+        let user: &Schema = schemas["User"].as_data().unwrap();
+        assert_eq!(
+            user.properties.as_ref().unwrap()["address"],
+            Referenceable::schema_ref("Address")
+        );
+        assert_eq!(
+            resolved.paths["/users/{id}"]
+                .get
+                .as_ref()
+                .unwrap()
+                .responses
+                .data["200"]
+                .as_data()
+                .unwrap()
+                .content
+                .as_ref()
+                .unwrap()["application/json"]
+                .schema,
+            Some(Referenceable::schema_ref("User"))
+        );
+    }
+
+    #[test]
+    fn should_report_missing_files_and_cycles() {
+        let missing = OpenAPIV3::default().add_path(
+            "/",
+            serde_json::from_value(serde_json::json!({"get": {"responses": {
+                "200": {"$ref": "./missing.yaml#/Ok"}
+            }}}))
+            .unwrap(),
+        );
+        assert!(matches!(
+            missing.resolve_external(&fixture()),
+            Err(ResolveError::Missing { .. })
+        ));
+
+        let cycle = OpenAPIV3::default().add_path(
+            "/",
+            serde_json::from_value(serde_json::json!({"get": {"responses": {
+                "200": {"description": "ok", "content": {"application/json": {
+                    "schema": {"$ref": "./schemas/cycle.yaml#/A"}
+                }}}
+            }}}))
+            .unwrap(),
+        );
+        assert!(matches!(
+            cycle.resolve_external(&fixture()),
+            Err(ResolveError::Cycle(_))
+        ));
+    }
+
+    #[test]
+    fn should_leave_remote_references_alone() {
+        let spec = OpenAPIV3::default().add_path(
+            "/users",
+            serde_json::from_value(serde_json::json!({"get": {"responses": {
+                "200": {"description": "user", "content": {"application/json": {
+                    "schema": {"$ref": "./schemas/user.yaml#/User"}
+                }}},
+                "default": {"description": "error", "content": {"application/json": {
+                    "schema": {"$ref": "https://example.com/schemas/error.json#/Error"}
+                }}}
+            }}}))
+            .unwrap(),
+        );
+        let resolved = spec.resolve_external(&fixture()).unwrap();
+
+        let references = resolved.all_references();
+        assert!(references.contains(&"#/components/schemas/User".to_string()));
+        assert!(references.contains(&"https://example.com/schemas/error.json#/Error".to_string()));
+        assert!(!resolved.to_string().contains(".yaml"));
+    }
+
+    #[test]
+    fn should_bundle_schemas_from_two_files() {
+        let spec: OpenAPIV3 =
+            serde_json::from_str(include_str!("../examples/v3.0/external/bundle.json")).unwrap();
+        let bundled = spec.resolve_external(&fixture()).unwrap();
+
+        assert!(bundled
+            .all_references()
+            .iter()
+            .all(|reference| reference.starts_with("#/components/schemas/")));
+        let schemas = bundled
+            .components
+            .as_ref()
+            .unwrap()
+            .schemas
+            .as_ref()
+            .unwrap();
+        let mut names: Vec<&String> = schemas.keys().collect();
+        names.sort();
+        assert_eq!(names, vec!["Address", "Address2", "Order", "User"]);
+
+        let order: &Schema = schemas["Order"].as_data().unwrap();
+        let properties = order.properties.as_ref().unwrap();
+        assert_eq!(properties["buyer"], Referenceable::schema_ref("User"));
+        assert_eq!(properties["shipTo"], Referenceable::schema_ref("Address"));
+        let user: &Schema = schemas["User"].as_data().unwrap();
+        assert_eq!(
+            user.properties.as_ref().unwrap()["address"],
+            Referenceable::schema_ref("Address2")
+        );
+        assert_eq!(bundled, spec.resolve_external(&fixture()).unwrap());
+    }
+}
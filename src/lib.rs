@@ -1,18 +1,180 @@
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_with::skip_serializing_none;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+
+pub mod builders;
+pub mod convert;
+pub mod diff;
+pub mod export;
+#[cfg(feature = "fs")]
+mod external;
+mod minify;
+mod refs;
+pub mod resolved;
+pub mod runtime_expr;
+#[cfg(feature = "schemars")]
+mod schemars_support;
+mod style;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod transform;
+mod validation;
+pub mod visit;
+
+pub use refs::{ComponentUsageReport, KindUsage, ResolveError};
+#[cfg(feature = "schemars")]
+pub use schemars_support::schema_from_type;
+pub use style::SerializeError;
+pub use transform::{FlattenError, RenameError};
+pub use validation::SchemaViolation;
+
+/// The map used for every keyed collection of the document.
+///
+/// It is a `BTreeMap` by default, which sorts keys on serialization. Enabling the `indexmap` feature swaps it for an `IndexMap` so that paths, properties and response codes keep their insertion order through round-trips.
+#[cfg(not(feature = "indexmap"))]
+pub type Map<K, V> = std::collections::BTreeMap<K, V>;
+#[cfg(feature = "indexmap")]
+pub type Map<K, V> = indexmap::IndexMap<K, V>;
 
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum Referenceable<T> {
-    Data(T),
     Reference(Reference),
+    Data(T),
+}
+
+impl<T> Referenceable<T> {
+    pub fn is_data(&self) -> bool {
+        matches!(self, Referenceable::Data(_))
+    }
+    pub fn is_reference(&self) -> bool {
+        matches!(self, Referenceable::Reference(_))
+    }
+    pub fn as_data(&self) -> Option<&T> {
+        match self {
+            Referenceable::Data(data) => Some(data),
+            Referenceable::Reference(_) => None,
+        }
+    }
+    pub fn as_reference(&self) -> Option<&Reference> {
+        match self {
+            Referenceable::Data(_) => None,
+            Referenceable::Reference(reference) => Some(reference),
+        }
+    }
+    pub fn data_mut(&mut self) -> Option<&mut T> {
+        match self {
+            Referenceable::Data(data) => Some(data),
+            Referenceable::Reference(_) => None,
+        }
+    }
+    pub fn into_data(self) -> Option<T> {
+        match self {
+            Referenceable::Data(data) => Some(data),
+            Referenceable::Reference(_) => None,
+        }
+    }
+    /// a reference to `#/components/{component}/{name}`
+    pub fn component_ref(component: &str, name: &str) -> Self {
+        Referenceable::Reference(Reference {
+            _ref: format!("#/components/{}/{}", component, name),
+        })
+    }
+    /// # Panics
+    /// Panics if it is a reference rather than inline data.
+    pub fn unwrap_data(self) -> T {
+        match self {
+            Referenceable::Data(data) => data,
+            Referenceable::Reference(reference) => panic!(
+                "called `Referenceable::unwrap_data()` on a reference to `{}`",
+                reference._ref
+            ),
+        }
+    }
+}
+
+/// Objects that can be held by [`Components`] and referenced from elsewhere in the document.
+pub trait Component: Sized {
+    /// The key of the components map holding this kind of object, e.g. `schemas`.
+    const KIND: &'static str;
+    fn components_map(components: &Components) -> Option<&Map<String, Referenceable<Self>>>;
+}
+
+impl<T: Component> Referenceable<T> {
+    /// Resolve a local `#/components/...` reference, following chained references. External references, dangling references and reference cycles resolve to `None`.
+    pub fn resolve<'a>(&'a self, components: &'a Components) -> Option<&'a T> {
+        let mut current = self;
+        let hops = T::components_map(components).map_or(0, |map| map.len());
+        for _ in 0..=hops {
+            match current {
+                Referenceable::Data(data) => return Some(data),
+                Referenceable::Reference(reference) => {
+                    let name = reference.component_name(T::KIND)?;
+                    current = T::components_map(components)?.get(&name)?;
+                }
+            }
+        }
+        None
+    }
+}
+
+macro_rules! impl_component {
+    ($($t:ty => $method:ident, $component:literal, $field:ident;)+) => {
+        $(
+        impl Component for $t {
+            const KIND: &'static str = $component;
+            fn components_map(components: &Components) -> Option<&Map<String, Referenceable<Self>>> {
+                components.$field.as_ref()
+            }
+        }
+        impl Referenceable<$t> {
+            #[doc = concat!("a reference to `#/components/", $component, "/{name}`")]
+            pub fn $method(name: &str) -> Self {
+                Self::component_ref($component, name)
+            }
+        }
+        )+
+    };
+}
+impl_component! {
+    Schema => schema_ref, "schemas", schemas;
+    Response => response_ref, "responses", responses;
+    Parameter => parameter_ref, "parameters", parameters;
+    Example => example_ref, "examples", examples;
+    RequestBody => request_body_ref, "requestBodies", request_bodies;
+    Header => header_ref, "headers", headers;
+    SecurityScheme => security_scheme_ref, "securitySchemes", security_schemes;
+    Link => link_ref, "links", links;
+    Callback => callback_ref, "callbacks", callbacks;
+}
+
+impl Referenceable<Parameter> {
+    /// set `required` on an inline parameter, leaving a reference untouched
+    pub fn with_required(mut self, required: bool) -> Self {
+        if let Referenceable::Data(parameter) = &mut self {
+            parameter.required = Some(required);
+        }
+        self
+    }
+}
+
+impl Referenceable<RequestBody> {
+    /// an inline request body with `application/json` content carrying the given schema
+    pub fn json_body(schema: Referenceable<Schema>) -> Self {
+        Referenceable::Data(crate::builders::request_body().json(schema).build())
+    }
+    /// Set `required` on an inline request body, leaving a reference untouched. A body is optional unless marked required.
+    pub fn required(mut self, required: bool) -> Self {
+        if let Referenceable::Data(body) = &mut self {
+            body.required = Some(required);
+        }
+        self
+    }
 }
 
-#[skip_serializing_none]
 /// the root document object of openAPI v3.0
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OpenAPIV3 {
     /// This string MUST be the semantic version number of the OpenAPI Specification version that the OpenAPI document uses. The `openapi` field SHOULD be used by tooling specifications and clients to interpret the OpenAPI document. This is not related to the API info.version string.
@@ -22,8 +184,8 @@ pub struct OpenAPIV3 {
     /// An array of Server Objects, which provide connectivity information to a target server. If the `servers` property is not provided, or is an empty array, the default value would be a `Server` Object with a url value of `/`.
     pub servers: Option<Vec<Server>>,
     /// The available paths and operations for the API.
-    pub paths: BTreeMap<String, PathItem>,
-    /// An element to hold various schemas for the specification.
+    pub paths: Map<String, PathItem>,
+    /// An element to hold various schemas for the specification. Left out when serialized if it holds nothing, see [`Components::is_empty`].
     pub components: Option<Components>,
     /// A declaration of which security mechanisms can be used across the API. The list of values includes alternative security requirement objects that can be used. Only one of the security requirement objects need to be satisfied to authorize a request. Individual operations can override this definition. To make security optional, an empty security requirement (`{}`) can be included in the array.
     pub security: Option<Vec<SecurityRequirement>>,
@@ -32,12 +194,533 @@ pub struct OpenAPIV3 {
     /// Additional external documentation.
     pub external_docs: Option<ExternalDocumentation>,
     #[serde(flatten)]
-    pub extras: Option<BTreeMap<String, Any>>,
+    pub extras: Option<Map<String, Any>>,
+    /// Write out the implicit default server when serializing, see [`OpenAPIV3::with_explicit_default_server`].
+    #[serde(skip)]
+    pub explicit_default_server: bool,
+}
+
+impl Serialize for OpenAPIV3 {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        /// The fields as written out, with the default server and empty components decided.
+        #[skip_serializing_none]
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Document<'a> {
+            openapi: &'a str,
+            info: &'a Info,
+            servers: Option<std::borrow::Cow<'a, [Server]>>,
+            paths: &'a Map<String, PathItem>,
+            components: Option<&'a Components>,
+            security: Option<&'a Vec<SecurityRequirement>>,
+            tags: Option<&'a Vec<Tag>>,
+            external_docs: Option<&'a ExternalDocumentation>,
+            #[serde(flatten)]
+            extras: Option<&'a Map<String, Any>>,
+        }
+
+        let servers = match self.servers.as_deref() {
+            None | Some([]) if self.explicit_default_server => {
+                Some(std::borrow::Cow::Owned(vec![Server::new("/")]))
+            }
+            servers => servers.map(std::borrow::Cow::Borrowed),
+        };
+        Document {
+            openapi: &self.openapi,
+            info: &self.info,
+            servers,
+            paths: &self.paths,
+            components: self
+                .components
+                .as_ref()
+                .filter(|components| !components.is_empty()),
+            security: self.security.as_ref(),
+            tags: self.tags.as_ref(),
+            external_docs: self.external_docs.as_ref(),
+            extras: self.extras.as_ref(),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// The parts of an [`OpenAPIV3`] document describing the API rather than its operations, see [`OpenAPIV3::parse_metadata_only`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct InfoAndComponents {
+    pub openapi: String,
+    pub info: Info,
+    pub servers: Option<Vec<Server>>,
+    pub tags: Option<Vec<Tag>>,
+    pub components: Option<Components>,
+}
+
+/// Why a string can not be used as the `openapi` version of an [`OpenAPIV3`] document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionError {
+    /// a well-formed OpenAPI version this crate does not model, e.g. `3.1.0`
+    Unsupported(String),
+    /// not an OpenAPI version at all
+    Invalid(String),
+}
+
+impl std::fmt::Display for VersionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VersionError::Unsupported(version) => write!(
+                f,
+                "OpenAPI `{}` is not supported, only 3.0.0 to 3.0.3 are",
+                version
+            ),
+            VersionError::Invalid(version) => write!(f, "`{}` is not an OpenAPI version", version),
+        }
+    }
+}
+
+impl std::error::Error for VersionError {}
+
+impl Default for OpenAPIV3 {
+    fn default() -> Self {
+        OpenAPIV3::new(Info::new("", ""))
+    }
+}
+
+impl OpenAPIV3 {
+    pub fn new(info: Info) -> OpenAPIV3 {
+        Self {
+            openapi: "3.0.0".to_string(),
+            info,
+            servers: None,
+            paths: Map::new(),
+            components: None,
+            security: None,
+            tags: None,
+            external_docs: None,
+            extras: None,
+            explicit_default_server: false,
+        }
+    }
+
+    /// Set the `openapi` version, accepting only the 3.0 releases this crate models, `3.0.0` to `3.0.3`.
+    ///
+    /// Prefer this over assigning the public `openapi` field, which accepts anything.
+    pub fn set_openapi_version(&mut self, version: &str) -> Result<(), VersionError> {
+        let parts: Vec<&str> = version.split('.').collect();
+        let numeric = parts.len() == 3
+            && parts
+                .iter()
+                .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+        if !numeric {
+            return Err(VersionError::Invalid(version.to_string()));
+        }
+        if !matches!(version, "3.0.0" | "3.0.1" | "3.0.2" | "3.0.3") {
+            return Err(VersionError::Unsupported(version.to_string()));
+        }
+        self.openapi = version.to_string();
+        Ok(())
+    }
+    /// Parse a JSON document from bytes, e.g. a file read with `std::fs::read`.
+    ///
+    /// A convenience wrapper around `serde_json::from_slice`, allocating as much as parsing with `serde_json::from_str`.
+    pub fn from_slice(bytes: &[u8]) -> Result<OpenAPIV3, serde_json::Error> {
+        serde_json::from_slice(bytes)
+    }
+    /// Parse a JSON document, accepting strings where a boolean or a number is expected, e.g. `required: "true"` or `minimum: "0"`, as hand-written documents often have them.
+    ///
+    /// Only the typed boolean and number keywords are coerced; examples, defaults, enums and extensions are left untouched. Parsing stays strict everywhere else.
+    pub fn parse_lenient(json: &str) -> Result<OpenAPIV3, serde_json::Error> {
+        let mut value: Any = serde_json::from_str(json)?;
+        coerce_lenient(&mut value);
+        serde_json::from_value(value)
+    }
+    /// Parse only `openapi`, `info`, `servers`, `tags` and `components` of a JSON document.
+    ///
+    /// `paths` and every other field are skipped without being modelled, so they only need to be well-formed JSON. Useful for tools indexing many large documents.
+    pub fn parse_metadata_only(json: &str) -> Result<InfoAndComponents, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+    pub fn add_server(mut self, server: Server) -> Self {
+        self.servers.get_or_insert_with(Vec::new).push(server);
+        self
+    }
+    /// Spell out the implicit default server: if `servers` is missing or empty when serializing, a single server with url `/` is written instead, which is what the specification assumes.
+    ///
+    /// `servers` itself is left alone, so servers added later replace the default.
+    pub fn with_explicit_default_server(mut self) -> Self {
+        self.explicit_default_server = true;
+        self
+    }
+    pub fn add_tag(mut self, tag: Tag) -> Self {
+        self.tags.get_or_insert_with(Vec::new).push(tag);
+        self
+    }
+    pub fn add_tags(&mut self, tags: impl IntoIterator<Item = Tag>) -> &mut Self {
+        self.tags.get_or_insert_with(Vec::new).extend(tags);
+        self
+    }
+    /// append several document-wide security requirements, any one of which authorizes a request
+    pub fn add_security_requirements(
+        &mut self,
+        requirements: impl IntoIterator<Item = SecurityRequirement>,
+    ) -> &mut Self {
+        self.security
+            .get_or_insert_with(Vec::new)
+            .extend(requirements);
+        self
+    }
+    /// register a security scheme under `components.securitySchemes`, creating the maps as needed
+    pub fn add_security_scheme(
+        &mut self,
+        name: impl Into<String>,
+        scheme: Referenceable<SecurityScheme>,
+    ) -> &mut Self {
+        self.components
+            .get_or_insert_with(Components::new)
+            .security_schemes
+            .get_or_insert_with(Map::new)
+            .insert(name.into(), scheme);
+        self
+    }
+    /// append a document-wide security requirement
+    pub fn add_security(&mut self, requirement: SecurityRequirement) -> &mut Self {
+        self.security.get_or_insert_with(Vec::new).push(requirement);
+        self
+    }
+    /// Require `requirements` for every operation that does not declare its own `security`.
+    ///
+    /// Operations can opt out with [`Operation::public`]. To make authentication optional instead, include an empty requirement (`SecurityRequirement::default()`, serialized as `{}`) among the alternatives.
+    pub fn with_default_security(mut self, requirements: Vec<SecurityRequirement>) -> Self {
+        self.security = Some(requirements);
+        self
+    }
+    pub fn add_path(mut self, path: impl Into<String>, item: PathItem) -> Self {
+        self.paths.insert(path.into(), item);
+        self
+    }
+    pub fn add_paths<K: Into<String>>(
+        mut self,
+        paths: impl IntoIterator<Item = (K, PathItem)>,
+    ) -> Self {
+        self.paths
+            .extend(paths.into_iter().map(|(path, item)| (path.into(), item)));
+        self
+    }
+    pub fn get_path(&self, path: &str) -> Option<&PathItem> {
+        self.paths.get(path)
+    }
+    pub fn get_path_mut(&mut self, path: &str) -> Option<&mut PathItem> {
+        self.paths.get_mut(path)
+    }
+    pub fn has_path(&self, path: &str) -> bool {
+        self.paths.contains_key(path)
+    }
+    /// Every operation of the document with its path and method.
+    pub fn operations(&self) -> impl Iterator<Item = (&str, HttpMethod, &Operation)> {
+        self.paths.iter().flat_map(|(path, item)| {
+            item.operations()
+                .map(move |(method, operation)| (path.as_str(), method, operation))
+        })
+    }
+    /// The path and method of every deprecated operation.
+    pub fn deprecated_operations(&self) -> Vec<(String, HttpMethod)> {
+        self.operations()
+            .filter(|(_, _, operation)| operation.is_deprecated())
+            .map(|(path, method, _)| (path.to_string(), method))
+            .collect()
+    }
+    /// The path, method and status of every success (`2XX`) response declaring no `content`, which usually means an under-specified endpoint.
+    ///
+    /// `204 No Content` is expected to have none and is never listed. Referenced responses are resolved through `components`; unresolvable ones are skipped.
+    pub fn responses_without_content(&self) -> Vec<(String, HttpMethod, String)> {
+        let mut found = vec![];
+        for (path, method, operation) in self.operations() {
+            for (status, response) in operation.success_responses() {
+                if status == "204" {
+                    continue;
+                }
+                let empty = self.resolve(response).is_some_and(|response| {
+                    response
+                        .content
+                        .as_ref()
+                        .is_none_or(|content| content.is_empty())
+                });
+                if empty {
+                    found.push((path.to_string(), method, status.to_string()));
+                }
+            }
+        }
+        found
+    }
+    /// Add a response to every operation that does not already define `status`.
+    pub fn add_common_response(&mut self, status: &str, response: Referenceable<Response>) {
+        for item in self.paths.values_mut() {
+            for (_, operation) in item.operations_mut() {
+                if !operation.responses.data.contains_key(status) {
+                    operation.add_response(status, response.clone());
+                }
+            }
+        }
+    }
+    /// The scopes of every OAuth2 security scheme: the ones declared by its flows plus the ones required by the top-level and operation `security`.
+    pub fn all_oauth_scopes(&self) -> BTreeMap<String, BTreeSet<String>> {
+        let mut scopes: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        let schemes = self
+            .components
+            .iter()
+            .flat_map(|components| components.security_schemes.iter().flatten());
+        for (name, scheme) in schemes {
+            if let Some(SecurityScheme {
+                _type: SecurityType::Oauth2 { flows },
+                ..
+            }) = self.resolve(scheme)
+            {
+                let declared = [
+                    &flows.implicit,
+                    &flows.password,
+                    &flows.client_credentials,
+                    &flows.authorization_code,
+                ]
+                .into_iter()
+                .flatten()
+                .flat_map(|flow| flow.scopes.keys().cloned());
+                scopes.entry(name.clone()).or_default().extend(declared);
+            }
+        }
+
+        let requirements = self.security.iter().flatten().chain(
+            self.operations()
+                .flat_map(|(_, _, operation)| operation.security.iter().flatten()),
+        );
+        for requirement in requirements {
+            for (name, required) in &requirement.data {
+                if let Some(declared) = scopes.get_mut(name) {
+                    declared.extend(required.iter().cloned());
+                }
+            }
+        }
+        scopes
+    }
+    /// Every `x-` extension of the document, keyed by name, with the `#/...` pointer of each object carrying it and its value.
+    ///
+    /// The root, tags, path items, operations, parameters, request bodies, responses and schemas are searched. Schemas are followed into their nested properties and items, both in `components` and inline in parameters and in the content of request bodies and responses.
+    pub fn all_extensions(&self) -> BTreeMap<String, Vec<(String, Any)>> {
+        type Found = BTreeMap<String, Vec<(String, Any)>>;
+        fn collect(location: &str, extensions: Option<&Map<String, Any>>, found: &mut Found) {
+            let extensions = extensions.into_iter().flatten();
+            for (key, value) in extensions.filter(|(key, _)| key.starts_with("x-")) {
+                found
+                    .entry(key.clone())
+                    .or_default()
+                    .push((location.to_string(), value.clone()));
+            }
+        }
+        fn parameters(location: &str, parameters: &[Referenceable<Parameter>], found: &mut Found) {
+            for (index, parameter) in parameters.iter().enumerate() {
+                if let Referenceable::Data(parameter) = parameter {
+                    let location = format!("{}/parameters/{}", location, index);
+                    collect(&location, parameter.extensions(), found);
+                    if let Some(schema) = &parameter.schema {
+                        schemas(&format!("{}/schema", location), schema, found);
+                    }
+                }
+            }
+        }
+        fn content(location: &str, content: Option<&Map<String, MediaType>>, found: &mut Found) {
+            for (content_type, media_type) in content.into_iter().flatten() {
+                if let Some(schema) = &media_type.schema {
+                    let location = format!(
+                        "{}/content/{}/schema",
+                        location,
+                        transform::escape(content_type)
+                    );
+                    schemas(&location, schema, found);
+                }
+            }
+        }
+        fn schemas(location: &str, schema: &Referenceable<Schema>, found: &mut Found) {
+            let Referenceable::Data(schema) = schema else {
+                return;
+            };
+            collect(location, schema.extensions(), found);
+            for (name, property) in schema.properties.iter().flatten() {
+                let location = format!("{}/properties/{}", location, transform::escape(name));
+                schemas(&location, property, found);
+            }
+            if let Some(items) = &schema.items {
+                schemas(&format!("{}/items", location), items, found);
+            }
+        }
+
+        let mut found = Found::new();
+        collect("#", self.extensions(), &mut found);
+        for (index, tag) in self.tags.iter().flatten().enumerate() {
+            collect(&format!("#/tags/{}", index), tag.extensions(), &mut found);
+        }
+        for (path, item) in &self.paths {
+            let location = format!("#/paths/{}", transform::escape(path));
+            collect(&location, item.extensions(), &mut found);
+            parameters(
+                &location,
+                item.parameters.as_deref().unwrap_or_default(),
+                &mut found,
+            );
+            for (method, operation) in item.operations() {
+                let location = format!("{}/{}", location, method.as_str());
+                collect(&location, operation.extensions(), &mut found);
+                let operation_parameters = operation.parameters.as_deref().unwrap_or_default();
+                parameters(&location, operation_parameters, &mut found);
+                if let Some(Referenceable::Data(body)) = &operation.request_body {
+                    let location = format!("{}/requestBody", location);
+                    collect(&location, body.extensions(), &mut found);
+                    content(&location, Some(&body.content), &mut found);
+                }
+                let responses = operation
+                    .responses
+                    .default
+                    .iter()
+                    .map(|response| ("default", response));
+                let responses = responses.chain(
+                    operation
+                        .responses
+                        .data
+                        .iter()
+                        .map(|(status, response)| (status.as_str(), response)),
+                );
+                for (status, response) in responses {
+                    if let Referenceable::Data(response) = response {
+                        let location = format!("{}/responses/{}", location, status);
+                        collect(&location, response.extensions(), &mut found);
+                        content(&location, response.content.as_ref(), &mut found);
+                    }
+                }
+            }
+        }
+        if let Some(components) = &self.components {
+            for (name, parameter) in components.parameters.iter().flatten() {
+                if let Referenceable::Data(parameter) = parameter {
+                    let location = format!("#/components/parameters/{}", transform::escape(name));
+                    collect(&location, parameter.extensions(), &mut found);
+                    if let Some(schema) = &parameter.schema {
+                        schemas(&format!("{}/schema", location), schema, &mut found);
+                    }
+                }
+            }
+            for (name, response) in components.responses.iter().flatten() {
+                if let Referenceable::Data(response) = response {
+                    let location = format!("#/components/responses/{}", transform::escape(name));
+                    collect(&location, response.extensions(), &mut found);
+                    content(&location, response.content.as_ref(), &mut found);
+                }
+            }
+            for (name, body) in components.request_bodies.iter().flatten() {
+                if let Referenceable::Data(body) = body {
+                    let location =
+                        format!("#/components/requestBodies/{}", transform::escape(name));
+                    collect(&location, body.extensions(), &mut found);
+                    content(&location, Some(&body.content), &mut found);
+                }
+            }
+            for (name, component) in components.schemas.iter().flatten() {
+                let location = format!("#/components/schemas/{}", transform::escape(name));
+                schemas(&location, component, &mut found);
+            }
+        }
+        found
+    }
+    /// Every tag name referenced by an operation.
+    pub fn used_tags(&self) -> BTreeSet<String> {
+        self.operations()
+            .flat_map(|(_, _, operation)| operation.tags.iter().flatten())
+            .cloned()
+            .collect()
+    }
+    /// How many operations carry each tag. An operation with several tags counts toward each, and untagged operations are counted under `""`.
+    pub fn operations_per_tag(&self) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        for (_, _, operation) in self.operations() {
+            match operation.tags.as_deref() {
+                Some(tags) if !tags.is_empty() => {
+                    for tag in tags {
+                        *counts.entry(tag.clone()).or_default() += 1;
+                    }
+                }
+                _ => *counts.entry(String::new()).or_default() += 1,
+            }
+        }
+        counts
+    }
+    /// The tags used by operations but missing from the top-level `tags` list.
+    pub fn undeclared_tags(&self) -> BTreeSet<String> {
+        let mut used = self.used_tags();
+        for tag in self.tags.iter().flatten() {
+            used.remove(&tag.name);
+        }
+        used
+    }
+    /// Every media type used as a `content` key by request bodies, responses, parameters and headers, or as the `contentType` of an encoding, in paths, callbacks and components.
+    ///
+    /// An encoding's `contentType` may list several media types separated by commas; each counts on its own.
+    pub fn media_types(&self) -> BTreeSet<String> {
+        #[derive(Default)]
+        struct MediaTypes(BTreeSet<String>);
+        impl visit::Visitor for MediaTypes {
+            fn visit_media_type(&mut self, content_type: &str, media_type: &MediaType) {
+                self.0.insert(content_type.to_owned());
+                let encodings = media_type.encoding.iter().flat_map(Map::values);
+                for content_type in encodings.filter_map(|encoding| encoding.content_type.as_ref())
+                {
+                    self.0.extend(
+                        content_type
+                            .split(',')
+                            .map(str::trim)
+                            .filter(|it| !it.is_empty())
+                            .map(str::to_owned),
+                    );
+                }
+            }
+        }
+
+        let mut found = MediaTypes::default();
+        visit::walk(self, &mut found);
+        found.0
+    }
+    /// Drop every path item that has no operations left, see [`PathItem::is_empty`].
+    pub fn remove_empty_paths(&mut self) {
+        self.paths.retain(|_, item| !item.is_empty());
+    }
+    /// Remove a path, keeping the order of the remaining ones.
+    pub fn remove_path(&mut self, path: &str) -> Option<PathItem> {
+        #[cfg(not(feature = "indexmap"))]
+        return self.paths.remove(path);
+        #[cfg(feature = "indexmap")]
+        return self.paths.shift_remove(path);
+    }
+    /// Look up an arbitrary node by JSON pointer, e.g. `/paths/~1users/get/responses/200`.
+    ///
+    /// `~1` and `~0` in a segment stand for `/` and `~` as in RFC 6901, and a leading `#` is accepted. The document is serialized to find the node, so an owned value is returned.
+    pub fn pointer(&self, ptr: &str) -> Option<Any> {
+        let ptr = ptr.strip_prefix('#').unwrap_or(ptr);
+        serde_json::to_value(self)
+            .ok()?
+            .pointer_mut(ptr)
+            .map(std::mem::take)
+    }
+    /// Resolve a referenceable object through this document's components.
+    pub fn resolve<'a, T: Component>(
+        &'a self,
+        referenceable: &'a Referenceable<T>,
+    ) -> Option<&'a T> {
+        match referenceable {
+            Referenceable::Data(data) => Some(data),
+            Referenceable::Reference(_) => self
+                .components
+                .as_ref()
+                .and_then(|components| referenceable.resolve(components)),
+        }
+    }
 }
 
 /// The object provides metadata about the API. The metadata MAY be used by the clients if needed, and MAY be presented in editing or documentation generation tools for convenience.
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Info {
     /// The title of the API.
@@ -54,9 +737,50 @@ pub struct Info {
     pub version: String,
 }
 
+impl Info {
+    pub fn new(title: impl Into<String>, version: impl Into<String>) -> Info {
+        Self {
+            title: title.into(),
+            description: None,
+            terms_of_service: None,
+            contact: None,
+            license: None,
+            version: version.into(),
+        }
+    }
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+    /// set the URL of the terms of service
+    ///
+    /// ```rust
+    /// use oas::Info;
+    ///
+    /// let info = Info::new("Petstore", "1.0.0").with_terms_of_service("https://example.com/terms");
+    /// assert_eq!(info.terms_of_service.as_deref(), Some("https://example.com/terms"));
+    /// ```
+    pub fn with_terms_of_service(mut self, terms_of_service: impl Into<String>) -> Self {
+        self.terms_of_service = Some(terms_of_service.into());
+        self
+    }
+    pub fn with_contact(mut self, contact: Contact) -> Self {
+        self.contact = Some(contact);
+        self
+    }
+    pub fn with_license(mut self, license: License) -> Self {
+        self.license = Some(license);
+        self
+    }
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = version.into();
+        self
+    }
+}
+
 /// Contact information for the exposed API.
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Contact {
     /// The identifying name of the contact person/organization.
     pub name: Option<String>,
@@ -68,29 +792,51 @@ pub struct Contact {
 
 /// License information for the exposed API.
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct License {
     /// The license name used for the API.
     pub name: String,
     /// A URL to the license used for the API. MUST be in the format of a URL.
     pub url: Option<String>,
+    /// An SPDX license expression for the API. The `identifier` field is mutually exclusive of the `url` field.
+    pub identifier: Option<String>,
+}
+
+impl License {
+    pub fn new(name: impl Into<String>) -> License {
+        Self {
+            name: name.into(),
+            url: None,
+            identifier: None,
+        }
+    }
+    /// `url` and `identifier` are mutually exclusive, only set one of them.
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+    /// `url` and `identifier` are mutually exclusive, only set one of them.
+    pub fn with_identifier(mut self, identifier: impl Into<String>) -> Self {
+        self.identifier = Some(identifier.into());
+        self
+    }
 }
 
 /// An object representing a Server.
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Server {
     /// A URL to the target host. This URL supports Server Variables and MAY be relative, to indicate that the host location is relative to the location where the OpenAPI document is being served. Variable substitutions will be made when a variable is named in {brackets}.
     pub url: String,
     /// An optional string describing the host designated by the URL. CommonMark syntax MAY be used for rich text representation.
     pub description: Option<String>,
     /// A map between a variable name and its value. The value is used for substitution in the server's URL template.
-    pub variables: Option<BTreeMap<String, ServerVariable>>,
+    pub variables: Option<Map<String, ServerVariable>>,
 }
 
 /// An object representing a Server Variable for server URL template substitution.
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ServerVariable {
     /// An enumeration of string values to be used if the substitution options are from a limited set. The array SHOULD NOT be empty.
     #[serde(rename = "enum")]
@@ -101,34 +847,297 @@ pub struct ServerVariable {
     pub description: Option<String>,
 }
 
+impl ServerVariable {
+    pub fn new(default: impl Into<String>) -> ServerVariable {
+        Self {
+            _enum: None,
+            default: default.into(),
+            description: None,
+        }
+    }
+    /// Restrict the variable to a set of values. Fails if `values` is empty, the spec says the array SHOULD NOT be empty.
+    pub fn with_enum(mut self, values: Vec<String>) -> Result<Self, String> {
+        if values.is_empty() {
+            return Err("the enum of a server variable should not be empty".to_owned());
+        }
+        self._enum = Some(values);
+        Ok(self)
+    }
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+    /// Check that a declared `enum` is not empty and contains the `default` value.
+    pub fn validate(&self) -> Result<(), String> {
+        match &self._enum {
+            Some(values) if values.is_empty() => {
+                Err("the enum of a server variable should not be empty".to_owned())
+            }
+            Some(values) if !values.contains(&self.default) => Err(format!(
+                "default value `{}` is not one of the enum values {:?}",
+                self.default, values
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Why a server URL template could not be expanded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExpandError {
+    /// the URL uses a `{variable}` that is not declared in `variables`
+    UnknownVariable(String),
+    /// the value chosen for a variable is not one of its `enum` values
+    NotInEnum { variable: String, value: String },
+    /// a `{` is never closed
+    UnterminatedVariable,
+}
+
+impl std::fmt::Display for ExpandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExpandError::UnknownVariable(name) => write!(f, "unknown server variable `{}`", name),
+            ExpandError::NotInEnum { variable, value } => write!(
+                f,
+                "`{}` is not an allowed value of server variable `{}`",
+                value, variable
+            ),
+            ExpandError::UnterminatedVariable => write!(f, "unterminated `{{` in server url"),
+        }
+    }
+}
+
+impl std::error::Error for ExpandError {}
+
+impl Server {
+    pub fn new(url: impl Into<String>) -> Server {
+        Self {
+            url: url.into(),
+            description: None,
+            variables: None,
+        }
+    }
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+    pub fn with_variables(mut self, variables: Map<String, ServerVariable>) -> Self {
+        self.variables = Some(variables);
+        self
+    }
+    pub fn add_variable(mut self, name: impl Into<String>, variable: ServerVariable) -> Self {
+        self.variables
+            .get_or_insert_with(Map::new)
+            .insert(name.into(), variable);
+        self
+    }
+    /// Resolve the URL template, substituting every `{name}` with its override or else the variable's `default`.
+    pub fn expand(&self, overrides: &BTreeMap<String, String>) -> Result<String, ExpandError> {
+        let mut url = String::with_capacity(self.url.len());
+        let mut rest = self.url.as_str();
+        while let Some(start) = rest.find('{') {
+            url.push_str(&rest[..start]);
+            let end = rest[start..]
+                .find('}')
+                .ok_or(ExpandError::UnterminatedVariable)?
+                + start;
+            let name = &rest[start + 1..end];
+            let variable = self
+                .variables
+                .as_ref()
+                .and_then(|variables| variables.get(name))
+                .ok_or_else(|| ExpandError::UnknownVariable(name.to_owned()))?;
+            let value = overrides.get(name).unwrap_or(&variable.default);
+            if let Some(allowed) = &variable._enum {
+                if !allowed.is_empty() && !allowed.contains(value) {
+                    return Err(ExpandError::NotInEnum {
+                        variable: name.to_owned(),
+                        value: value.clone(),
+                    });
+                }
+            }
+            url.push_str(value);
+            rest = &rest[end + 1..];
+        }
+        url.push_str(rest);
+        Ok(url)
+    }
+}
+
 /// Holds a set of reusable objects for different aspects of the OAS. All objects defined within the components object will have no effect on the API unless they are explicitly referenced from properties outside the components object.
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Components {
     /// An object to hold reusable Schema Objects.
-    pub schemas: Option<BTreeMap<String, Referenceable<Schema>>>,
+    pub schemas: Option<Map<String, Referenceable<Schema>>>,
     /// An object to hold reusable Response Objects.
-    pub responses: Option<BTreeMap<String, Referenceable<Response>>>,
+    pub responses: Option<Map<String, Referenceable<Response>>>,
     /// An object to hold reusable Parameter Objects.
-    pub parameters: Option<BTreeMap<String, Referenceable<Parameter>>>,
+    pub parameters: Option<Map<String, Referenceable<Parameter>>>,
     /// An object to hold reusable Example Objects.
-    pub examples: Option<BTreeMap<String, Referenceable<Example>>>,
+    pub examples: Option<Map<String, Referenceable<Example>>>,
     /// An object to hold reusable Request Body Objects.
-    pub request_bodies: Option<BTreeMap<String, Referenceable<RequestBody>>>,
+    pub request_bodies: Option<Map<String, Referenceable<RequestBody>>>,
     /// An object to hold reusable Header Objects.
-    pub headers: Option<BTreeMap<String, Referenceable<Header>>>,
+    pub headers: Option<Map<String, Referenceable<Header>>>,
     /// An object to hold reusable Security Scheme Objects.
-    pub security_schemes: Option<BTreeMap<String, Referenceable<SecurityScheme>>>,
+    pub security_schemes: Option<Map<String, Referenceable<SecurityScheme>>>,
     /// An object to hold reusable Link Objects.
-    pub links: Option<BTreeMap<String, Referenceable<Link>>>,
+    pub links: Option<Map<String, Referenceable<Link>>>,
     /// An object to hold reusable Callback Objects.
-    pub callbacks: Option<BTreeMap<String, Referenceable<Callback>>>,
+    pub callbacks: Option<Map<String, Referenceable<Callback>>>,
+}
+
+macro_rules! impl_components_setters {
+    ($($field:ident: $t:ty => $with:ident, $add:ident;)+) => {
+        impl Components {
+            pub fn new() -> Components {
+                Self::default()
+            }
+            /// whether no map holds any component, missing and empty maps alike
+            pub fn is_empty(&self) -> bool {
+                $(self.$field.as_ref().is_none_or(|map| map.is_empty()))&&+
+            }
+            $(
+            #[doc = concat!("replace the whole `", stringify!($field), "` map")]
+            pub fn $with(mut self, $field: Map<String, Referenceable<$t>>) -> Self {
+                self.$field = Some($field);
+                self
+            }
+            #[doc = concat!("insert a single entry into `", stringify!($field), "`")]
+            pub fn $add(mut self, name: impl Into<String>, item: Referenceable<$t>) -> Self {
+                self.$field
+                    .get_or_insert_with(Map::new)
+                    .insert(name.into(), item);
+                self
+            }
+            )+
+        }
+    };
+}
+impl_components_setters! {
+    schemas: Schema => with_schemas, add_schema;
+    responses: Response => with_responses, add_response;
+    parameters: Parameter => with_parameters, add_parameter;
+    examples: Example => with_examples, add_example;
+    request_bodies: RequestBody => with_request_bodies, add_request_body;
+    headers: Header => with_headers, add_header;
+    security_schemes: SecurityScheme => with_security_schemes, add_security_scheme;
+    links: Link => with_links, add_link;
+    callbacks: Callback => with_callbacks, add_callback;
+}
+
+impl Components {
+    /// components holding only the given schemas
+    pub fn from_schemas<K: Into<String>>(
+        schemas: impl IntoIterator<Item = (K, Referenceable<Schema>)>,
+    ) -> Components {
+        schemas
+            .into_iter()
+            .map(|(name, schema)| (name.into(), schema))
+            .collect()
+    }
+}
+
+impl FromIterator<(String, Referenceable<Schema>)> for Components {
+    fn from_iter<I: IntoIterator<Item = (String, Referenceable<Schema>)>>(iter: I) -> Self {
+        Components::new().with_schemas(iter.into_iter().collect())
+    }
+}
+
+/// The HTTP methods a [`PathItem`] can define an operation for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum HttpMethod {
+    Get,
+    Put,
+    Post,
+    Delete,
+    Options,
+    Head,
+    Patch,
+    Trace,
+}
+
+impl HttpMethod {
+    /// every method, in the order the fields are declared on [`PathItem`]
+    pub const ALL: [HttpMethod; 8] = [
+        HttpMethod::Get,
+        HttpMethod::Put,
+        HttpMethod::Post,
+        HttpMethod::Delete,
+        HttpMethod::Options,
+        HttpMethod::Head,
+        HttpMethod::Patch,
+        HttpMethod::Trace,
+    ];
+
+    /// the lowercase field name used in a path item, e.g. `get`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HttpMethod::Get => "get",
+            HttpMethod::Put => "put",
+            HttpMethod::Post => "post",
+            HttpMethod::Delete => "delete",
+            HttpMethod::Options => "options",
+            HttpMethod::Head => "head",
+            HttpMethod::Patch => "patch",
+            HttpMethod::Trace => "trace",
+        }
+    }
+}
+
+impl std::fmt::Display for HttpMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A string naming none of the methods a [`PathItem`] can hold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseMethodError(pub String);
+
+impl std::fmt::Display for ParseMethodError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown HTTP method `{}`", self.0)
+    }
+}
+
+impl std::error::Error for ParseMethodError {}
+
+impl std::str::FromStr for HttpMethod {
+    type Err = ParseMethodError;
+
+    /// Parse a method name in any case, e.g. `get` or `GET`.
+    fn from_str(method: &str) -> Result<Self, Self::Err> {
+        HttpMethod::ALL
+            .into_iter()
+            .find(|it| it.as_str().eq_ignore_ascii_case(method))
+            .ok_or_else(|| ParseMethodError(method.to_string()))
+    }
+}
+
+/// Two path items define the same method with different operations, see [`PathItem::merge`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict {
+    pub method: HttpMethod,
+}
+
+impl std::fmt::Display for MergeConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "both path items define a different {} operation",
+            self.method
+        )
+    }
 }
 
+impl std::error::Error for MergeConflict {}
+
 /// Describes the operations available on a single path. A Path Item MAY be empty, due to ACL constraints. The path itself is still exposed to the documentation viewer but they will not know which operations and parameters are available.
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct PathItem {
     /// Allows for an external definition of this path item. The referenced structure MUST be in the format of a Path Item Object. In case a Path Item Object field appears both in the defined object and the referenced object, the behavior is undefined.
     #[serde(rename = "$ref")]
@@ -157,11 +1166,13 @@ pub struct PathItem {
     pub servers: Option<Vec<Server>>,
     /// A list of parameters that are applicable for all the operations described under this path. These parameters can be overridden at the operation level, but cannot be removed there. The list MUST NOT include duplicated parameters. A unique parameter is defined by a combination of a name and location. The list can use the Reference Object to link to parameters that are defined at the OpenAPI Object's components/parameters.
     pub parameters: Option<Vec<Referenceable<Parameter>>>,
+    #[serde(flatten)]
+    pub extras: Option<Map<String, Any>>,
 }
 
 /// Describes a single API operation on a path.
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Operation {
     /// A list of tags for API documentation control. Tags can be used for logical grouping of operations by resources or any other qualifier.
@@ -181,38 +1192,329 @@ pub struct Operation {
     /// The list of possible responses as they are returned from executing this operation.
     pub responses: Responses,
     /// A map of possible out-of band callbacks related to the parent operation. The key is a unique identifier for the Callback Object. Each value in the map is a Callback Object that describes a request that may be initiated by the API provider and the expected responses.
-    pub callbacks: Option<BTreeMap<String, Referenceable<Callback>>>,
+    pub callbacks: Option<Map<String, Referenceable<Callback>>>,
     /// Declares this operation to be deprecated. Consumers SHOULD refrain from usage of the declared operation. Default value is `false`.
     pub deprecated: Option<bool>,
     /// A declaration of which security mechanisms can be used for this operation. The list of values includes alternative security requirement objects that can be used. Only one of the security requirement objects need to be satisfied to authorize a request. To make security optional, an empty security requirement (`{}`) can be included in the array. This definition overrides any declared top-level security. To remove a top-level security declaration, an empty array can be used.
     pub security: Option<Vec<SecurityRequirement>>,
     /// An alternative server array to service this operation. If an alternative server object is specified at the Path Item Object or Root level, it will be overridden by this value.
     pub servers: Option<Vec<Server>>,
+    #[serde(flatten)]
+    pub extras: Option<Map<String, Any>>,
 }
 
-/// Allows referencing an external resource for extended documentation.
-#[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ExternalDocumentation {
-    /// A short description of the target documentation. CommonMark syntax MAY be used for rich text representation.
-    pub description: Option<String>,
-    /// The URL for the target documentation. Value MUST be in the format of a URL.
-    pub url: String,
-}
-
-/// The location of the parameter
-#[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum ParameterIn {
-    Query,
-    Header,
-    Path,
-    Cookie,
-}
-
-/// Describes a single operation parameter.
-/// A unique parameter is defined by a combination of a name and location.
+impl PathItem {
+    pub fn operation(&self, method: HttpMethod) -> Option<&Operation> {
+        match method {
+            HttpMethod::Get => self.get.as_ref(),
+            HttpMethod::Put => self.put.as_ref(),
+            HttpMethod::Post => self.post.as_ref(),
+            HttpMethod::Delete => self.delete.as_ref(),
+            HttpMethod::Options => self.options.as_ref(),
+            HttpMethod::Head => self.head.as_ref(),
+            HttpMethod::Patch => self.patch.as_ref(),
+            HttpMethod::Trace => self.trace.as_ref(),
+        }
+    }
+    pub fn operation_mut(&mut self, method: HttpMethod) -> &mut Option<Operation> {
+        match method {
+            HttpMethod::Get => &mut self.get,
+            HttpMethod::Put => &mut self.put,
+            HttpMethod::Post => &mut self.post,
+            HttpMethod::Delete => &mut self.delete,
+            HttpMethod::Options => &mut self.options,
+            HttpMethod::Head => &mut self.head,
+            HttpMethod::Patch => &mut self.patch,
+            HttpMethod::Trace => &mut self.trace,
+        }
+    }
+    /// Combine another definition of the same path into this one.
+    ///
+    /// Methods missing here are taken from `other`, and `summary`, `description`, `servers`, `$ref` and extensions are only taken when missing here. Parameters are told apart by name and location, or by `$ref` for references, and the ones defined here win. Nothing is changed if both define a method with different operations.
+    pub fn merge(&mut self, other: PathItem) -> Result<(), MergeConflict> {
+        for (method, operation) in other.operations() {
+            if self
+                .operation(method)
+                .is_some_and(|defined| defined != operation)
+            {
+                return Err(MergeConflict { method });
+            }
+        }
+        let PathItem {
+            _ref,
+            summary,
+            description,
+            get,
+            put,
+            post,
+            delete,
+            options,
+            head,
+            patch,
+            trace,
+            servers,
+            parameters,
+            extras,
+        } = other;
+        let operations = [get, put, post, delete, options, head, patch, trace];
+        for (method, operation) in HttpMethod::ALL.into_iter().zip(operations) {
+            let defined = self.operation_mut(method);
+            if defined.is_none() {
+                *defined = operation;
+            }
+        }
+        self._ref = self._ref.take().or(_ref);
+        self.summary = self.summary.take().or(summary);
+        self.description = self.description.take().or(description);
+        self.servers = self.servers.take().or(servers);
+        for parameter in parameters.into_iter().flatten() {
+            let defined = self.parameters.get_or_insert_with(Vec::new);
+            let key = parameter_key(&parameter, None);
+            if !defined
+                .iter()
+                .any(|defined| parameter_key(defined, None) == key)
+            {
+                defined.push(parameter);
+            }
+        }
+        for (key, value) in extras.into_iter().flatten() {
+            self.extras
+                .get_or_insert_with(Map::new)
+                .entry(key)
+                .or_insert(value);
+        }
+        Ok(())
+    }
+    /// Set an operation for each method, replacing any already defined.
+    pub fn with_operations(
+        mut self,
+        operations: impl IntoIterator<Item = (HttpMethod, Operation)>,
+    ) -> Self {
+        for (method, operation) in operations {
+            *self.operation_mut(method) = Some(operation);
+        }
+        self
+    }
+    /// [`PathItem::with_operations`] with methods named by strings in any case, failing on the first unknown one.
+    pub fn with_operations_str<'a>(
+        self,
+        operations: impl IntoIterator<Item = (&'a str, Operation)>,
+    ) -> Result<Self, ParseMethodError> {
+        let operations = operations
+            .into_iter()
+            .map(|(method, operation)| Ok((method.parse()?, operation)))
+            .collect::<Result<Vec<_>, ParseMethodError>>()?;
+        Ok(self.with_operations(operations))
+    }
+    /// Whether this path item points at another definition with `$ref`.
+    ///
+    /// The specification leaves the meaning of fields next to `$ref` undefined, so they should be treated as ignored: the referenced path item is the definition. [`PathItem::validate`] warns when such a path item declares operations anyway.
+    pub fn is_reference(&self) -> bool {
+        self._ref.is_some()
+    }
+    /// Warnings about definitions that are ignored, e.g. operations next to a `$ref`.
+    pub fn validate(&self) -> Vec<String> {
+        let Some(reference) = &self._ref else {
+            return vec![];
+        };
+        self.operations()
+            .map(|(method, _)| {
+                format!(
+                    "the {} operation is ignored because the path item refers to `{}`",
+                    method, reference
+                )
+            })
+            .collect()
+    }
+    /// Whether the path item defines no operation at all. `summary`, `description`, `servers` and `parameters` are ignored, but an item pointing elsewhere with `$ref` is never empty.
+    pub fn is_empty(&self) -> bool {
+        self._ref.is_none() && self.operations().next().is_none()
+    }
+    /// The parameters that apply to `op`: the ones declared on the path item, overridden by the operation's own parameters with the same name and location.
+    ///
+    /// References are resolved through `components` to learn their name and location. A parameter that can not be resolved is identified by its `$ref` instead, so it only overrides an identical reference. Inherited parameters come first in their declared order, followed by the operation's.
+    pub fn effective_parameters(
+        &self,
+        op: &Operation,
+        components: &Components,
+    ) -> Vec<Referenceable<Parameter>> {
+        let key = |parameter: &Referenceable<Parameter>| parameter_key(parameter, Some(components));
+        let own = op.parameters.as_deref().unwrap_or_default();
+        let overridden: Vec<_> = own.iter().map(key).collect();
+        self.parameters
+            .iter()
+            .flatten()
+            .filter(|parameter| !overridden.contains(&key(parameter)))
+            .chain(own)
+            .cloned()
+            .collect()
+    }
+    /// the defined operations along with their method
+    pub fn operations(&self) -> impl Iterator<Item = (HttpMethod, &Operation)> {
+        HttpMethod::ALL
+            .into_iter()
+            .filter_map(move |method| self.operation(method).map(|operation| (method, operation)))
+    }
+    pub fn operations_mut(&mut self) -> impl Iterator<Item = (HttpMethod, &mut Operation)> {
+        [
+            (HttpMethod::Get, &mut self.get),
+            (HttpMethod::Put, &mut self.put),
+            (HttpMethod::Post, &mut self.post),
+            (HttpMethod::Delete, &mut self.delete),
+            (HttpMethod::Options, &mut self.options),
+            (HttpMethod::Head, &mut self.head),
+            (HttpMethod::Patch, &mut self.patch),
+            (HttpMethod::Trace, &mut self.trace),
+        ]
+        .into_iter()
+        .filter_map(|(method, operation)| operation.as_mut().map(|operation| (method, operation)))
+    }
+}
+
+impl Operation {
+    pub fn add_callback(
+        mut self,
+        name: impl Into<String>,
+        callback: Referenceable<Callback>,
+    ) -> Self {
+        self.callbacks
+            .get_or_insert_with(Map::new)
+            .insert(name.into(), callback);
+        self
+    }
+    /// Serve this operation without authentication, whatever the document-wide `security` says.
+    ///
+    /// This sets `security` to an empty array, which the specification defines as removing the top-level requirements. An array holding an empty requirement (`[{}]`) would instead make authentication optional.
+    pub fn public(mut self) -> Self {
+        self.security = Some(vec![]);
+        self
+    }
+    /// add or replace the response for a status code in place
+    pub fn add_response(&mut self, status: impl Into<String>, response: Referenceable<Response>) {
+        self.responses.data.insert(status.into(), response);
+    }
+    /// append a parameter in place
+    pub fn add_parameter(&mut self, parameter: Referenceable<Parameter>) {
+        self.parameters.get_or_insert_with(Vec::new).push(parameter);
+    }
+    pub fn is_deprecated(&self) -> bool {
+        self.deprecated.unwrap_or(false)
+    }
+    /// The `2XX` responses, whether declared by exact code or range. `default` is never included.
+    pub fn success_responses(&self) -> impl Iterator<Item = (&str, &Referenceable<Response>)> {
+        self.responses_of_class(&['2'])
+    }
+    /// The `4XX` and `5XX` responses, whether declared by exact code or range. `default` is never included, see `responses.default`.
+    pub fn error_responses(&self) -> impl Iterator<Item = (&str, &Referenceable<Response>)> {
+        self.responses_of_class(&['4', '5'])
+    }
+    fn responses_of_class<'a>(
+        &'a self,
+        classes: &'a [char],
+    ) -> impl Iterator<Item = (&'a str, &'a Referenceable<Response>)> {
+        self.responses
+            .data
+            .iter()
+            .filter(move |(code, _)| {
+                code.len() == 3
+                    && code
+                        .chars()
+                        .next()
+                        .is_some_and(|class| classes.contains(&class))
+                    && (code[1..].chars().all(|c| c.is_ascii_digit())
+                        || code[1..].eq_ignore_ascii_case("xx"))
+            })
+            .map(|(code, response)| (code.as_str(), response))
+    }
+}
+
+/// Allows referencing an external resource for extended documentation.
+#[skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExternalDocumentation {
+    /// A short description of the target documentation. CommonMark syntax MAY be used for rich text representation.
+    pub description: Option<String>,
+    /// The URL for the target documentation. Value MUST be in the format of a URL.
+    pub url: String,
+}
+
+/// The location of the parameter
+#[skip_serializing_none]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParameterIn {
+    Query,
+    Header,
+    Path,
+    Cookie,
+}
+
+impl ParameterIn {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ParameterIn::Query => "query",
+            ParameterIn::Header => "header",
+            ParameterIn::Path => "path",
+            ParameterIn::Cookie => "cookie",
+        }
+    }
+}
+
+/// How a parameter value is serialized.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ParameterStyle {
+    /// Path-style parameters defined by RFC6570, e.g. `;id=5`.
+    Matrix,
+    /// Label style parameters defined by RFC6570, e.g. `.5`.
+    Label,
+    /// Form style parameters defined by RFC6570, e.g. `id=5`.
+    Form,
+    /// Simple style parameters defined by RFC6570, e.g. `5`.
+    Simple,
+    /// Space separated array values.
+    SpaceDelimited,
+    /// Pipe separated array values.
+    PipeDelimited,
+    /// A simple way of rendering nested objects using form parameters, e.g. `id[role]=admin`.
+    DeepObject,
+    /// Any style this crate does not know, kept so that unusual documents still parse.
+    #[serde(untagged)]
+    Other(String),
+}
+
+impl ParameterStyle {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ParameterStyle::Matrix => "matrix",
+            ParameterStyle::Label => "label",
+            ParameterStyle::Form => "form",
+            ParameterStyle::Simple => "simple",
+            ParameterStyle::SpaceDelimited => "spaceDelimited",
+            ParameterStyle::PipeDelimited => "pipeDelimited",
+            ParameterStyle::DeepObject => "deepObject",
+            ParameterStyle::Other(style) => style,
+        }
+    }
+}
+
+impl From<&str> for ParameterStyle {
+    fn from(style: &str) -> Self {
+        match style {
+            "matrix" => ParameterStyle::Matrix,
+            "label" => ParameterStyle::Label,
+            "form" => ParameterStyle::Form,
+            "simple" => ParameterStyle::Simple,
+            "spaceDelimited" => ParameterStyle::SpaceDelimited,
+            "pipeDelimited" => ParameterStyle::PipeDelimited,
+            "deepObject" => ParameterStyle::DeepObject,
+            other => ParameterStyle::Other(other.to_string()),
+        }
+    }
+}
+
+/// Describes a single operation parameter.
+/// A unique parameter is defined by a combination of a name and location.
 /// Parameter Locations
 /// There are four possible parameter locations specified by the in field:
 /// - path - Used together with Path Templating, where the parameter value is actually part of the operation's URL. This does not include the host or base path of the API. For example, in /items/{itemId}, the path parameter is itemId.
@@ -220,7 +1522,7 @@ pub enum ParameterIn {
 /// - header - Custom headers that are expected as part of the request. Note that RFC7230 states header names are case insensitive.
 /// - cookie - Used to pass a specific cookie value to the API.
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Parameter {
     /// The name of the parameter
@@ -236,8 +1538,8 @@ pub struct Parameter {
     pub deprecated: Option<bool>,
     /// Sets the ability to pass empty-valued parameters
     pub allow_empty_value: Option<bool>,
-    /// Describes how the parameter value will be serialized depending on the type of the parameter value
-    pub style: Option<String>,
+    /// Describes how the parameter value will be serialized depending on the type of the parameter value. Default values (based on value of `in`): for `query` - `form`; for `path` - `simple`; for `header` - `simple`; for `cookie` - `form`.
+    pub style: Option<ParameterStyle>,
     pub explode: Option<bool>,
     pub allow_reserved: Option<bool>,
     /// The schema defining the type used for the parameter.
@@ -245,89 +1547,424 @@ pub struct Parameter {
     /// Example of the parameter's potential value.
     pub example: Option<Any>,
     /// Examples of the parameter's potential value.
-    pub examples: Option<BTreeMap<String, Referenceable<Example>>>,
+    pub examples: Option<Map<String, Referenceable<Example>>>,
     /// A map containing the representations for the parameter. The key is the media type and the value describes it.
-    pub content: Option<BTreeMap<String, MediaType>>,
+    pub content: Option<Map<String, MediaType>>,
+    #[serde(flatten)]
+    pub extras: Option<Map<String, Any>>,
+}
+
+impl Parameter {
+    /// A parameter without schema or content. Path parameters are always required, so they start out `required`.
+    pub fn new(name: impl Into<String>, location: ParameterIn) -> Parameter {
+        Self {
+            name: name.into(),
+            _in: location,
+            description: None,
+            required: (location == ParameterIn::Path).then_some(true),
+            deprecated: None,
+            allow_empty_value: None,
+            style: None,
+            explode: None,
+            allow_reserved: None,
+            schema: None,
+            example: None,
+            examples: None,
+            content: None,
+            extras: None,
+        }
+    }
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+    pub fn with_required(mut self, required: bool) -> Self {
+        self.required = Some(required);
+        self
+    }
+    /// A parameter is described either by a `schema` or by `content`, never both.
+    pub fn with_schema(mut self, schema: Referenceable<Schema>) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+    pub fn with_example(mut self, example: Any) -> Self {
+        self.example = Some(example);
+        self
+    }
+    pub fn with_examples(mut self, examples: Map<String, Referenceable<Example>>) -> Self {
+        self.examples = Some(examples);
+        self
+    }
+    pub fn deprecated(mut self) -> Self {
+        self.deprecated = Some(true);
+        self
+    }
+    /// describe a complex parameter through a media type instead of a `schema`
+    ///
+    /// A parameter is described either by a `schema` or by `content`, never both, and `content` MUST hold exactly one entry.
+    ///
+    /// ```rust
+    /// use oas::{MediaType, Parameter, ParameterIn, Referenceable, Schema};
+    ///
+    /// let filter = Parameter::new("filter", ParameterIn::Query).with_content(
+    ///     "application/json",
+    ///     MediaType::new().with_schema(Referenceable::schema_ref("Filter")),
+    /// );
+    /// assert!(filter.schema.is_none());
+    /// assert_eq!(
+    ///     filter.to_value()["content"]["application/json"]["schema"]["$ref"],
+    ///     "#/components/schemas/Filter"
+    /// );
+    /// ```
+    pub fn with_content(mut self, media_type: impl Into<String>, content: MediaType) -> Self {
+        self.content
+            .get_or_insert_with(Map::new)
+            .insert(media_type.into(), content);
+        self
+    }
+    /// A parameter carrying the schema, style and examples of a header.
+    pub fn from_header(
+        name: impl Into<String>,
+        location: ParameterIn,
+        header: &Header,
+    ) -> Parameter {
+        Self {
+            name: name.into(),
+            _in: location,
+            description: header.description.clone(),
+            required: header.required,
+            deprecated: header.deprecated,
+            allow_empty_value: header.allow_empty_value,
+            style: header.style.as_deref().map(ParameterStyle::from),
+            explode: header.explode,
+            allow_reserved: header.allow_reserved,
+            schema: header.schema.clone(),
+            example: header.example.clone(),
+            examples: header.examples.clone(),
+            content: header.content.clone(),
+            extras: None,
+        }
+    }
+    /// The declared `style`, or the default for the parameter location: `form` for query and cookie parameters, `simple` for path and header parameters.
+    pub fn effective_style(&self) -> ParameterStyle {
+        self.style.clone().unwrap_or(match self._in {
+            ParameterIn::Query | ParameterIn::Cookie => ParameterStyle::Form,
+            ParameterIn::Path | ParameterIn::Header => ParameterStyle::Simple,
+        })
+    }
 }
 
 /// Describes a single request body.
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RequestBody {
     /// A brief description of the request body.
     pub description: Option<String>,
     /// Determines if the request body is required in the request. Defaults to `false`.
     pub required: Option<bool>,
     /// The content of the request body.
-    pub content: BTreeMap<String, MediaType>,
+    pub content: Map<String, MediaType>,
+    #[serde(flatten)]
+    pub extras: Option<Map<String, Any>>,
+}
+
+impl RequestBody {
+    /// The schema of the content for a media type such as `application/json`.
+    ///
+    /// Wildcard keys like `application/*` or `*/*` are used when no key matches exactly, and a wildcard media type picks the first content of that type.
+    pub fn schema_for(&self, media_type: &str) -> Option<&Referenceable<Schema>> {
+        schema_for(&self.content, media_type)
+    }
 }
 
 /// Each Media Type Object provides schema and examples for the media type identified by its key.
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct MediaType {
     /// The schema defining the content of the request, response, or parameter.
     pub schema: Option<Referenceable<Schema>>,
     /// Example of the media type.
     pub example: Option<Any>,
     /// Examples of the media type.
-    pub examples: Option<BTreeMap<String, Referenceable<Example>>>,
+    pub examples: Option<Map<String, Referenceable<Example>>>,
     /// A map between a property name and its encoding information.
-    pub encoding: Option<BTreeMap<String, Encoding>>,
+    pub encoding: Option<Map<String, Encoding>>,
+}
+
+impl MediaType {
+    pub fn new() -> MediaType {
+        Self::default()
+    }
+    pub fn with_schema(mut self, schema: Referenceable<Schema>) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+    pub fn with_example(mut self, example: Any) -> Self {
+        self.example = Some(example);
+        self
+    }
+    pub fn with_examples(mut self, examples: Map<String, Referenceable<Example>>) -> Self {
+        self.examples = Some(examples);
+        self
+    }
+    pub fn add_example(mut self, name: impl Into<String>, example: Referenceable<Example>) -> Self {
+        self.examples
+            .get_or_insert_with(Map::new)
+            .insert(name.into(), example);
+        self
+    }
+    /// the encoding only applies to `multipart` and `application/x-www-form-urlencoded` request bodies
+    pub fn with_encoding(mut self, encoding: Map<String, Encoding>) -> Self {
+        self.encoding = Some(encoding);
+        self
+    }
 }
 
 /// A single encoding definition applied to a single schema property.
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Encoding {
     /// The Content-Type for encoding a specific property.
     pub content_type: Option<String>,
     /// map allowing additional information to be provided as headers, for example `Content-Disposition`. `Content-Type` is described separately and SHALL be ignored in this section. This property SHALL be ignored if the request body media type is not a `multipart`.
-    pub headers: Option<BTreeMap<String, Referenceable<Header>>>,
+    pub headers: Option<Map<String, Referenceable<Header>>>,
     /// Describes how a specific property value will be serialized depending on its type.
     pub style: Option<String>,
     pub explode: Option<bool>,
     pub allow_reserved: Option<bool>,
 }
 
+impl Encoding {
+    /// ```rust
+    /// use oas::{Encoding, Header, Referenceable};
+    ///
+    /// let encoding = Encoding::new().with_content_type("image/png").add_header(
+    ///     "Content-Disposition",
+    ///     Referenceable::Data(Header {
+    ///         description: Some("the file name of the uploaded image".to_string()),
+    ///         ..Default::default()
+    ///     }),
+    /// );
+    /// assert_eq!(encoding.content_type.as_deref(), Some("image/png"));
+    /// assert!(encoding.headers.unwrap().contains_key("Content-Disposition"));
+    /// ```
+    pub fn new() -> Encoding {
+        Self::default()
+    }
+    pub fn with_content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+    pub fn with_style(mut self, style: impl Into<String>) -> Self {
+        self.style = Some(style.into());
+        self
+    }
+    pub fn explode(mut self, explode: bool) -> Self {
+        self.explode = Some(explode);
+        self
+    }
+    pub fn add_header(mut self, name: impl Into<String>, header: Referenceable<Header>) -> Self {
+        self.headers
+            .get_or_insert_with(Map::new)
+            .insert(name.into(), header);
+        self
+    }
+}
+
 /// A container for the expected responses of an operation. The container maps a HTTP response code to the expected response.
 /// The documentation is not necessarily expected to cover all possible HTTP response codes because they may not be known in advance. However, documentation is expected to cover a successful operation response and any known errors.
 /// The default MAY be used as a default response object for all HTTP codes that are not covered individually by the specification.
 /// The Responses Object MUST contain at least one response code, and it SHOULD be the response for a successful operation call.
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Responses {
     /// The documentation of responses other than the ones declared for specific HTTP response codes. Use this field to cover undeclared responses. A Reference Object can link to a response that the OpenAPI Object's components/responses section defines.
     pub default: Option<Referenceable<Response>>,
     #[serde(flatten)]
-    pub data: BTreeMap<String, Referenceable<Response>>,
+    pub data: Map<String, Referenceable<Response>>,
+}
+
+impl Responses {
+    pub fn new() -> Responses {
+        Self::default()
+    }
+    /// add the response for a status, the `default` key fills the `default` field
+    pub fn with_status(
+        mut self,
+        status: impl Into<String>,
+        response: Referenceable<Response>,
+    ) -> Self {
+        let status = status.into();
+        if status == "default" {
+            self.default = Some(response);
+        } else {
+            self.data.insert(status, response);
+        }
+        self
+    }
+    pub fn from_pairs<K: Into<String>>(
+        pairs: impl IntoIterator<Item = (K, Referenceable<Response>)>,
+    ) -> Responses {
+        pairs
+            .into_iter()
+            .fold(Responses::new(), |responses, (status, response)| {
+                responses.with_status(status, response)
+            })
+    }
+    /// The response declared under a key, `default` meaning the `default` field, e.g. `get("404")` or `get("2XX")`. Unlike [`Responses::response_for_status`] no range or `default` fallback happens.
+    pub fn get(&self, status: &str) -> Option<&Referenceable<Response>> {
+        match status {
+            "default" => self.default.as_ref(),
+            status => self.data.get(status),
+        }
+    }
+    pub fn contains(&self, status: &str) -> bool {
+        self.get(status).is_some()
+    }
+    /// Every response with its key, the codes first and `default` last.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Referenceable<Response>)> {
+        self.data
+            .iter()
+            .map(|(status, response)| (status.as_str(), response))
+            .chain(self.default.iter().map(|response| ("default", response)))
+    }
+    /// Pick the response documented for a concrete status: an exact code first, then its range among `1XX` to `5XX`, then `default`.
+    pub fn response_for_status(&self, status: u16) -> Option<&Referenceable<Response>> {
+        self.data
+            .get(&status.to_string())
+            .or_else(|| {
+                (100..600)
+                    .contains(&status)
+                    .then(|| self.data.get(&format!("{}XX", status / 100)))
+                    .flatten()
+            })
+            .or(self.default.as_ref())
+    }
+}
+
+impl FromIterator<(String, Referenceable<Response>)> for Responses {
+    fn from_iter<I: IntoIterator<Item = (String, Referenceable<Response>)>>(iter: I) -> Self {
+        Responses::from_pairs(iter)
+    }
 }
 
 /// Describes a single response from an API Operation, including design-time, static `links` to operations based on the response.
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Response {
     /// A short description of the response.
     pub description: String,
     /// Maps a header name to its definition.
-    pub headers: Option<BTreeMap<String, Referenceable<Header>>>,
+    pub headers: Option<Map<String, Referenceable<Header>>>,
     /// A map containing descriptions of potential response payloads.
-    pub content: Option<BTreeMap<String, MediaType>>,
+    pub content: Option<Map<String, MediaType>>,
     /// A map of operations links that can be followed from the response.
-    pub links: Option<BTreeMap<String, Referenceable<Link>>>,
+    pub links: Option<Map<String, Referenceable<Link>>>,
+    #[serde(flatten)]
+    pub extras: Option<Map<String, Any>>,
+}
+
+impl Response {
+    pub fn new(description: impl Into<String>) -> Response {
+        Self {
+            description: description.into(),
+            headers: None,
+            content: None,
+            links: None,
+            extras: None,
+        }
+    }
+    pub fn with_headers(mut self, headers: Map<String, Referenceable<Header>>) -> Self {
+        self.headers = Some(headers);
+        self
+    }
+    pub fn with_content(mut self, content: Map<String, MediaType>) -> Self {
+        self.content = Some(content);
+        self
+    }
+    pub fn with_links(mut self, links: Map<String, Referenceable<Link>>) -> Self {
+        self.links = Some(links);
+        self
+    }
+    /// The schema of the content for a media type, see [`RequestBody::schema_for`].
+    pub fn schema_for(&self, media_type: &str) -> Option<&Referenceable<Schema>> {
+        schema_for(self.content.as_ref()?, media_type)
+    }
+}
+
+/// Find the schema of the content best matching a media type.
+///
+/// An exact key wins, parameters such as `; charset=utf-8` aside, then a wildcard key like `application/*` and last `*/*`. A wildcard media type like `application/*` picks the first key of that type.
+/// Identify a parameter by its name and location, resolving references through `components` when given. A parameter that can not be resolved is identified by its `$ref`.
+fn parameter_key(
+    parameter: &Referenceable<Parameter>,
+    components: Option<&Components>,
+) -> (String, Option<ParameterIn>) {
+    let resolved = match components {
+        Some(components) => parameter.resolve(components),
+        None => parameter.as_data(),
+    };
+    match resolved {
+        Some(parameter) => (parameter.name.clone(), Some(parameter._in)),
+        None => (
+            parameter
+                .as_reference()
+                .map(|reference| reference._ref.clone())
+                .unwrap_or_default(),
+            None,
+        ),
+    }
+}
+
+fn schema_for<'a>(
+    content: &'a Map<String, MediaType>,
+    media_type: &str,
+) -> Option<&'a Referenceable<Schema>> {
+    let essence = |media_type: &str| {
+        let essence = media_type.split(';').next().unwrap_or_default();
+        essence.trim().to_ascii_lowercase()
+    };
+    let wanted = essence(media_type);
+    let (_type, subtype) = wanted.split_once('/')?;
+    let by_key = |matches: &dyn Fn(&str) -> bool| {
+        content
+            .iter()
+            .find(|(key, _)| matches(&essence(key)))
+            .map(|(_, media_type)| media_type)
+    };
+    let range = format!("{}/*", _type);
+    let found = by_key(&|key| key == wanted)
+        .or_else(|| by_key(&|key| key == range))
+        .or_else(|| by_key(&|key| key == "*/*"))
+        .or_else(|| match subtype {
+            "*" => by_key(&|key| key.split_once('/').is_some_and(|(key, _)| key == _type)),
+            _ => None,
+        })?;
+    found.schema.as_ref()
 }
 
 /// A map of possible out-of band callbacks related to the parent operation. Each value in the map is a Path Item Object that describes a set of requests that may be initiated by the API provider and the expected responses. The key value used to identify the path item object is an expression, evaluated at runtime, that identifies a URL to use for the callback operation.
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Callback {
     #[serde(flatten)]
-    pub data: BTreeMap<String, PathItem>,
+    pub data: Map<String, PathItem>,
+}
+
+impl Callback {
+    pub fn new() -> Callback {
+        Self::default()
+    }
+    /// add a path item keyed by a runtime expression, e.g. `{$request.body#/callbackUrl}`
+    pub fn add_expression(mut self, expression: impl Into<String>, path_item: PathItem) -> Self {
+        self.data.insert(expression.into(), path_item);
+        self
+    }
 }
 
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Example {
     /// Short description for the example.
@@ -343,15 +1980,15 @@ pub type Any = serde_json::Value;
 
 /// represents a possible design-time link for a response.
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Link {
     /// A relative or absolute URI reference to an OAS operation.
     pub operation_ref: Option<String>,
-    /// The name of an existing, resolvable OAS operation
-    pub operation_id: String,
+    /// The name of an existing, resolvable OAS operation. This field is mutually exclusive of the `operation_ref` field.
+    pub operation_id: Option<String>,
     /// A map representing parameters to pass to an operation as specified with `operation_id` or identified via `operation_ef`.
-    pub parameters: Option<BTreeMap<String, Any>>,
+    pub parameters: Option<Map<String, Any>>,
     /// A literal value or `{expression}` to use as a request body when calling the target operation.
     pub request_body: Option<Any>,
     /// A description of the link.
@@ -360,8 +1997,32 @@ pub struct Link {
     pub server: Option<Server>,
 }
 
+impl Link {
+    pub fn new() -> Link {
+        Self::default()
+    }
+    pub fn with_operation_id(mut self, operation_id: impl Into<String>) -> Self {
+        self.operation_id = Some(operation_id.into());
+        self
+    }
+    pub fn with_operation_ref(mut self, operation_ref: impl Into<String>) -> Self {
+        self.operation_ref = Some(operation_ref.into());
+        self
+    }
+    pub fn add_parameter(mut self, name: impl Into<String>, value: Any) -> Self {
+        self.parameters
+            .get_or_insert_with(Map::new)
+            .insert(name.into(), value);
+        self
+    }
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+}
+
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Header {
     pub description: Option<String>,
@@ -373,13 +2034,35 @@ pub struct Header {
     pub allow_reserved: Option<bool>,
     pub schema: Option<Referenceable<Schema>>,
     pub example: Option<Any>,
-    pub examples: Option<BTreeMap<String, Referenceable<Example>>>,
-    pub content: Option<BTreeMap<String, MediaType>>,
+    pub examples: Option<Map<String, Referenceable<Example>>>,
+    pub content: Option<Map<String, MediaType>>,
+}
+
+impl Header {
+    /// The header equivalent of a parameter, dropping its `name`, `in` and extensions.
+    pub fn from_parameter(parameter: &Parameter) -> Header {
+        Self {
+            description: parameter.description.clone(),
+            required: parameter.required,
+            deprecated: parameter.deprecated,
+            allow_empty_value: parameter.allow_empty_value,
+            style: parameter
+                .style
+                .as_ref()
+                .map(|style| style.as_str().to_string()),
+            explode: parameter.explode,
+            allow_reserved: parameter.allow_reserved,
+            schema: parameter.schema.clone(),
+            example: parameter.example.clone(),
+            examples: parameter.examples.clone(),
+            content: parameter.content.clone(),
+        }
+    }
 }
 
 /// Adds metadata to a single tag that is used by the `Operation` Object. It is not mandatory to have a Tag Object per tag defined in the Operation Object instances.
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Tag {
     /// The name of the tag.
@@ -388,6 +2071,8 @@ pub struct Tag {
     pub description: Option<String>,
     /// Additional external documentation for this tag.
     pub external_docs: Option<ExternalDocumentation>,
+    #[serde(flatten)]
+    pub extras: Option<Map<String, Any>>,
 }
 
 impl Tag {
@@ -396,57 +2081,428 @@ impl Tag {
             name: name.into(),
             description: description.into(),
             external_docs: None,
+            extras: None,
         }
     }
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+    /// point to further documentation of the tag
+    ///
+    /// ```rust
+    /// use oas::{ExternalDocumentation, Tag};
+    ///
+    /// let tag = Tag::new("pets", None)
+    ///     .with_description("Everything about your pets")
+    ///     .with_external_docs(ExternalDocumentation {
+    ///         description: Some("Find out more".to_string()),
+    ///         url: "https://example.com/docs/pets".to_string(),
+    ///     });
+    /// assert_eq!(tag.to_value()["externalDocs"]["url"], "https://example.com/docs/pets");
+    /// ```
+    pub fn with_external_docs(mut self, external_docs: ExternalDocumentation) -> Self {
+        self.external_docs = Some(external_docs);
+        self
+    }
 }
 
 /// A simple object to allow referencing other components in the specification, internally and externally.
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Reference {
     /// The reference string.
     #[serde(rename = "$ref")]
     pub _ref: String,
 }
 
+impl Reference {
+    /// The name of the referenced component if this is a local reference of the given kind, e.g. `Pet` for `#/components/schemas/Pet` and kind `schemas`.
+    pub fn component_name(&self, kind: &str) -> Option<String> {
+        let name = self
+            ._ref
+            .strip_prefix("#/components/")?
+            .strip_prefix(kind)?
+            .strip_prefix('/')?;
+        if name.contains('/') {
+            return None;
+        }
+        Some(name.replace("~1", "/").replace("~0", "~"))
+    }
+}
+
 /// The Schema Object allows the definition of input and output data types. These types can be objects, but also primitives and arrays.
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Schema {
     #[serde(rename = "type")]
     pub _type: Option<String>,
     pub format: Option<String>,
     pub nullable: Option<bool>,
     pub description: Option<String>,
-    #[serde(flatten)]
-    pub extras: BTreeMap<String, Any>,
+    /// The properties of an object schema, keyed by property name.
+    pub properties: Option<Map<String, Referenceable<Schema>>>,
+    /// The names of the properties an object MUST contain.
+    pub required: Option<Vec<String>>,
+    /// The schema of every element of an array schema.
+    pub items: Option<Box<Referenceable<Schema>>>,
+    #[serde(rename = "enum")]
+    pub _enum: Option<Vec<Any>>,
+    pub default: Option<Any>,
+    /// A free-form example of an instance for this schema.
+    pub example: Option<Any>,
+    pub minimum: Option<serde_json::Number>,
+    pub exclusive_minimum: Option<bool>,
+    pub maximum: Option<serde_json::Number>,
+    pub exclusive_maximum: Option<bool>,
+    pub min_length: Option<u64>,
+    pub max_length: Option<u64>,
+    /// Additional metadata for describing the XML representation of this schema's values.
+    pub xml: Option<Xml>,
+    /// Additional external documentation for this schema.
+    pub external_docs: Option<ExternalDocumentation>,
+    /// Every other keyword, e.g. `allOf`, and the `x-` extensions. They are always serialized after the fields above, keywords sorted by name and extensions last, so diffs stay stable whatever order they were parsed in.
+    #[serde(flatten, serialize_with = "serialize_ordered")]
+    pub extras: Map<String, Any>,
 }
 
-/// When request bodies or response payloads may be one of a number of different schemas, a `discriminator` object can be used to aid in serialization, deserialization, and validation. The discriminator is a specific object in a schema which is used to inform the consumer of the specification of an alternative schema based on the value associated with it.
+/// Keys of the maps keyed by names or status codes rather than keywords, e.g. `responses` holding a `default` response or `properties` holding a property named `example`.
+pub(crate) const NAMED_MAPS: [&str; 12] = [
+    "paths",
+    "responses",
+    "properties",
+    "schemas",
+    "parameters",
+    "headers",
+    "requestBodies",
+    "securitySchemes",
+    "links",
+    "callbacks",
+    "content",
+    "encoding",
+];
+
+/// Turn `"true"`, `"false"` and numeric strings under typed keywords into proper values, see [`OpenAPIV3::parse_lenient`].
+fn coerce_lenient(value: &mut Any) {
+    const BOOLEANS: [&str; 10] = [
+        "required",
+        "deprecated",
+        "allowEmptyValue",
+        "explode",
+        "allowReserved",
+        "nullable",
+        "exclusiveMinimum",
+        "exclusiveMaximum",
+        "attribute",
+        "wrapped",
+    ];
+    const NUMBERS: [&str; 4] = ["minimum", "maximum", "minLength", "maxLength"];
+    match value {
+        Any::Object(object) => {
+            for (key, value) in object.iter_mut() {
+                if let (true, Any::Object(map)) = (NAMED_MAPS.contains(&key.as_str()), &mut *value)
+                {
+                    map.values_mut().for_each(coerce_lenient);
+                    continue;
+                }
+                if matches!(key.as_str(), "example" | "examples" | "default" | "enum")
+                    || key.starts_with("x-")
+                {
+                    continue;
+                }
+                if let Any::String(string) = value {
+                    if BOOLEANS.contains(&key.as_str()) {
+                        if let Ok(boolean) = string.parse::<bool>() {
+                            *value = Any::Bool(boolean);
+                        }
+                    } else if NUMBERS.contains(&key.as_str()) {
+                        if let Ok(number) = string.trim().parse::<serde_json::Number>() {
+                            *value = Any::Number(number);
+                        }
+                    }
+                } else {
+                    coerce_lenient(value);
+                }
+            }
+        }
+        Any::Array(array) => array.iter_mut().for_each(coerce_lenient),
+        _ => {}
+    }
+}
+
+fn serialize_ordered<S: serde::Serializer>(
+    extras: &Map<String, Any>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut keys: Vec<&String> = extras.keys().collect();
+    keys.sort_by_key(|key| (key.starts_with("x-"), *key));
+    serializer.collect_map(keys.into_iter().map(|key| (key, &extras[key])))
+}
+
+impl Schema {
+    /// `type: object`, or no `type` at all but `properties`
+    pub fn is_object(&self) -> bool {
+        match self._type.as_deref() {
+            Some(_type) => _type == "object",
+            None => self.properties.is_some(),
+        }
+    }
+    pub fn is_array(&self) -> bool {
+        self._type.as_deref() == Some("array")
+    }
+    pub fn is_string(&self) -> bool {
+        self._type.as_deref() == Some("string")
+    }
+    /// `type` is `string`, `number`, `integer` or `boolean`
+    pub fn is_primitive(&self) -> bool {
+        matches!(
+            self._type.as_deref(),
+            Some("string" | "number" | "integer" | "boolean")
+        )
+    }
+    /// `nullable: true`; a schema is not nullable by default
+    pub fn is_nullable(&self) -> bool {
+        self.nullable == Some(true)
+    }
+    pub fn with_xml(mut self, xml: Xml) -> Self {
+        self.xml = Some(xml);
+        self
+    }
+    pub fn with_external_docs(mut self, external_docs: ExternalDocumentation) -> Self {
+        self.external_docs = Some(external_docs);
+        self
+    }
+    /// A schema only matching `value`.
+    ///
+    /// OpenAPI 3.0 has no `const` keyword, so this is encoded as a one-element `enum`, the form generators recognize, e.g. `{"enum": ["dog"]}` for the `kind` property of a discriminated union.
+    pub fn constant(value: Any) -> Schema {
+        Schema {
+            _enum: Some(vec![value]),
+            ..Default::default()
+        }
+    }
+    /// Call `f` with every property of this object schema and, recursively, of its nested objects, along with its dotted path from the root, e.g. `address.city`.
+    ///
+    /// Properties contributed by `allOf` members are visited as if declared here. References are resolved through `components`; a property whose reference can not be resolved is skipped. A schema that refers back to one being walked, e.g. a tree node's `children`, is reported but not entered again.
+    pub fn walk_properties(&self, components: &Components, f: &mut dyn FnMut(&str, &Schema)) {
+        fn walk(
+            schema: &Schema,
+            prefix: &str,
+            components: &Components,
+            stack: &mut Vec<String>,
+            f: &mut dyn FnMut(&str, &Schema),
+        ) {
+            for (name, property) in schema.properties.iter().flatten() {
+                let path = match prefix {
+                    "" => name.clone(),
+                    prefix => format!("{}.{}", prefix, name),
+                };
+                if let Some(resolved) = property.resolve(components) {
+                    f(&path, resolved);
+                    enter(property, resolved, &path, components, stack, f);
+                }
+            }
+            for member in &schema.subschemas("allOf") {
+                if let Some(resolved) = member.resolve(components) {
+                    enter(member, resolved, prefix, components, stack, f);
+                }
+            }
+        }
+        fn enter(
+            schema: &Referenceable<Schema>,
+            resolved: &Schema,
+            path: &str,
+            components: &Components,
+            stack: &mut Vec<String>,
+            f: &mut dyn FnMut(&str, &Schema),
+        ) {
+            let name = schema
+                .as_reference()
+                .and_then(|reference| reference.component_name("schemas"));
+            if let Some(name) = name {
+                if stack.contains(&name) {
+                    return;
+                }
+                stack.push(name);
+                walk(resolved, path, components, stack, f);
+                stack.pop();
+            } else {
+                walk(resolved, path, components, stack, f);
+            }
+        }
+        walk(self, "", components, &mut vec![], f);
+    }
+    /// How deeply schemas nest below and including this one, through `properties`, `items`, `additionalProperties` and the `allOf`, `oneOf`, `anyOf` and `not` keywords, e.g. 1 for `{"type": "string"}`.
+    ///
+    /// References are resolved through `components` and unresolvable ones count as a single level. A reference back to a schema already being measured makes the depth unbounded, reported as `usize::MAX`.
+    pub fn depth(&self, components: &Components) -> usize {
+        fn depth(schema: &Schema, components: &Components, stack: &mut Vec<String>) -> usize {
+            let children = schema
+                .properties
+                .iter()
+                .flat_map(|properties| properties.values().cloned())
+                .chain(schema.items.iter().map(|items| (**items).clone()))
+                .chain(
+                    ["additionalProperties", "allOf", "oneOf", "anyOf", "not"]
+                        .into_iter()
+                        .flat_map(|keyword| schema.subschemas(keyword)),
+                );
+            let mut deepest = 0;
+            for child in children {
+                let name = child
+                    .as_reference()
+                    .and_then(|reference| reference.component_name("schemas"));
+                if name.as_ref().is_some_and(|name| stack.contains(name)) {
+                    return usize::MAX;
+                }
+                let Some(resolved) = child.resolve(components) else {
+                    deepest = deepest.max(1);
+                    continue;
+                };
+                stack.extend(name.clone());
+                let child_depth = depth(resolved, components, stack);
+                if name.is_some() {
+                    stack.pop();
+                }
+                if child_depth == usize::MAX {
+                    return usize::MAX;
+                }
+                deepest = deepest.max(child_depth);
+            }
+            deepest + 1
+        }
+        depth(self, components, &mut vec![])
+    }
+    /// the schemas held by an untyped keyword, either a single schema such as `not` or an array such as `allOf`
+    fn subschemas(&self, keyword: &str) -> Vec<Referenceable<Schema>> {
+        let values = match self.extras.get(keyword) {
+            Some(Any::Array(values)) => values.iter().collect(),
+            Some(value @ Any::Object(_)) => vec![value],
+            _ => vec![],
+        };
+        values
+            .into_iter()
+            .filter_map(|value| serde_json::from_value(value.clone()).ok())
+            .collect()
+    }
+    /// The single value of a one-element `enum`, as produced by [`Schema::constant`].
+    pub fn is_constant(&self) -> Option<&Any> {
+        match self._enum.as_deref() {
+            Some([value]) => Some(value),
+            _ => None,
+        }
+    }
+    /// Produce a representative value for this schema, e.g. to feed a mock server.
+    ///
+    /// An explicit `example`, then `default`, then the first `enum` value is preferred. Otherwise a placeholder is derived from the `type`: objects are built from their `properties` and arrays hold a single element built from `items`. References can not be resolved here and yield `null`.
+    pub fn example_value(&self) -> Any {
+        if let Some(value) = self
+            .example
+            .as_ref()
+            .or(self.default.as_ref())
+            .or_else(|| self._enum.as_ref().and_then(|values| values.first()))
+        {
+            return value.clone();
+        }
+        fn inline_example(schema: &Referenceable<Schema>) -> Any {
+            schema
+                .as_data()
+                .map(Schema::example_value)
+                .unwrap_or(Any::Null)
+        }
+        let object = || {
+            Any::Object(
+                self.properties
+                    .iter()
+                    .flatten()
+                    .map(|(name, property)| (name.clone(), inline_example(property)))
+                    .collect(),
+            )
+        };
+        match self._type.as_deref() {
+            Some("string") => Any::from("string"),
+            Some("integer") => Any::from(0),
+            Some("number") => Any::from(0.0),
+            Some("boolean") => Any::from(true),
+            Some("array") => Any::Array(
+                self.items
+                    .iter()
+                    .map(|items| inline_example(items))
+                    .collect(),
+            ),
+            Some("object") => object(),
+            None if self.properties.is_some() => object(),
+            _ => Any::Null,
+        }
+    }
+}
 
+/// When request bodies or response payloads may be one of a number of different schemas, a `discriminator` object can be used to aid in serialization, deserialization, and validation. The discriminator is a specific object in a schema which is used to inform the consumer of the specification of an alternative schema based on the value associated with it.
+///
 /// When using the discriminator, inline schemas will not be considered.
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Discriminator {
     /// The name of the property in the payload that will hold the discriminator value.
     pub property_name: String,
     /// An object to hold mappings between payload values and schema names or references.
-    pub maapping: Option<BTreeMap<String, String>>,
+    pub maapping: Option<Map<String, String>>,
+}
+
+/// A metadata object that allows for more fine-tuned XML model definitions.
+///
+/// When using arrays, XML element names are not inferred (for singular/plural forms) and the `name` property SHOULD be used to add that information.
+#[skip_serializing_none]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Xml {
+    /// Replaces the name of the element/attribute used for the described schema property. When defined within `items`, it will affect the name of the individual XML elements within the list.
+    pub name: Option<String>,
+    /// The URI of the namespace definition. Value MUST be in the form of an absolute URI.
+    pub namespace: Option<String>,
+    /// The prefix to be used for the name.
+    pub prefix: Option<String>,
+    /// Declares whether the property definition translates to an attribute instead of an element. Default value is `false`.
+    pub attribute: Option<bool>,
+    /// MAY be used only for an array definition. Signifies whether the array is wrapped (for example, `<books><book/><book/></books>`) or unwrapped (`<book/><book/>`). Default value is `false`.
+    pub wrapped: Option<bool>,
+    #[serde(flatten)]
+    pub extras: Option<Map<String, Any>>,
+}
+
+/// The location of an API key, which unlike a parameter can not live in the path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiKeyLocation {
+    Query,
+    Header,
+    Cookie,
+}
+
+impl From<ApiKeyLocation> for ParameterIn {
+    fn from(location: ApiKeyLocation) -> Self {
+        match location {
+            ApiKeyLocation::Query => ParameterIn::Query,
+            ApiKeyLocation::Header => ParameterIn::Header,
+            ApiKeyLocation::Cookie => ParameterIn::Cookie,
+        }
+    }
 }
 
 /// The type of the security scheme.
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[serde(tag = "type")]
+#[allow(clippy::large_enum_variant)]
 pub enum SecurityType {
     ApiKey {
         /// The name of the header
         name: String,
         /// The location of the API key. Valid values are `query`, `header or `cookie`.
         #[serde(rename = "in")]
-        _in: ParameterIn,
+        _in: ApiKeyLocation,
     },
     Http {
         /// The name of the HTTP Authorization scheme to be used in the Authorization header as defined in RFC7235. The values used SHOULD be registered in the IANA Authentication Scheme registry.
@@ -463,11 +2519,14 @@ pub enum SecurityType {
         /// OpenId Connect URL to discover OAuth2 configuration values. This MUST be in the form of a URL.
         open_id_connect_url: String,
     },
+    /// Mutual TLS, where the client authenticates with a certificate.
+    #[serde(rename = "mutualTLS")]
+    MutualTls,
 }
 
 /// Defines a security scheme that can be used by the operations.
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SecurityScheme {
     #[serde(flatten)]
     pub _type: SecurityType,
@@ -477,7 +2536,7 @@ pub struct SecurityScheme {
 
 // todo should be enum
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OauthFlows {
     /// Configuration for the OAuth Implicit flow
@@ -490,28 +2549,116 @@ pub struct OauthFlows {
     pub authorization_code: Option<OauthFlow>,
 }
 
+impl OauthFlows {
+    /// Report every flow missing a URL the specification requires for it.
+    pub fn validate(&self) -> Vec<String> {
+        let flows = [
+            ("implicit", &self.implicit, true, false),
+            ("password", &self.password, false, true),
+            ("clientCredentials", &self.client_credentials, false, true),
+            ("authorizationCode", &self.authorization_code, true, true),
+        ];
+        let mut errors = vec![];
+        for (name, flow, needs_authorization_url, needs_token_url) in flows {
+            let Some(flow) = flow else { continue };
+            if needs_authorization_url && flow.authorization_url.is_none() {
+                errors.push(format!("{} flow is missing authorizationUrl", name));
+            }
+            if needs_token_url && flow.token_url.is_none() {
+                errors.push(format!("{} flow is missing tokenUrl", name));
+            }
+        }
+        errors
+    }
+}
+
 /// Configuration details for a supported OAuth Flow
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OauthFlow {
-    /// The authorization URL to be used for this flow. This MUST be in the form of a URL.
-    pub authorization_url: String,
-    /// he token URL to be used for this flow. This MUST be in the form of a URL.
+    /// The authorization URL to be used for this flow. This MUST be in the form of a URL. Required by the `implicit` and `authorizationCode` flows.
+    pub authorization_url: Option<String>,
+    /// The token URL to be used for this flow. Required by the `password`, `clientCredentials` and `authorizationCode` flows. This MUST be in the form of a URL.
     pub token_url: Option<String>,
     /// The URL to be used for obtaining refresh tokens. This MUST be in the form of a URL.
     pub refresh_url: Option<String>,
     /// The available scopes for the OAuth2 security scheme. A map between the scope name and a short description for it. The map MAY be empty.
-    pub scopes: BTreeMap<String, String>,
+    pub scopes: Map<String, String>,
 }
 
 /// Lists the required security schemes to execute this operation.
 #[skip_serializing_none]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct SecurityRequirement {
     #[serde(flatten)]
-    pub data: BTreeMap<String, Vec<String>>,
+    pub data: Map<String, Vec<String>>,
+}
+
+/// Objects that can carry specification extensions, i.e. `x-` prefixed fields.
+pub trait Extensible {
+    fn extensions(&self) -> Option<&Map<String, Any>>;
+    fn extensions_mut(&mut self) -> &mut Map<String, Any>;
+
+    /// store a serializable value as an extension, prefixing the key with `x-` if missing
+    ///
+    /// Fails, leaving the extensions untouched, if `value` is not representable as JSON, e.g. a map with non-string keys.
+    fn set_extension(&mut self, key: &str, value: impl Serialize) -> Result<(), serde_json::Error> {
+        let value = serde_json::to_value(value)?;
+        self.extensions_mut().insert(extension_key(key), value);
+        Ok(())
+    }
+    /// read an extension back as `T`, returning `None` if it is missing or has another shape
+    fn get_extension<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.extensions()
+            .and_then(|extensions| extensions.get(&extension_key(key)))
+            .and_then(|value| T::deserialize(value).ok())
+    }
+}
+
+fn extension_key(key: &str) -> String {
+    if key.starts_with("x-") {
+        key.to_owned()
+    } else {
+        format!("x-{}", key)
+    }
+}
+
+macro_rules! impl_extensions {
+    ($($st:ty,)+) => {
+        $(
+        impl $st {
+            /// add a specification extension, the key SHOULD begin with `x-`
+            pub fn add_extension(mut self, key: impl Into<String>, value: Any) -> Self {
+                self.extras
+                    .get_or_insert_with(Map::new)
+                    .insert(key.into(), value);
+                self
+            }
+        }
+        impl Extensible for $st {
+            fn extensions(&self) -> Option<&Map<String, Any>> {
+                self.extras.as_ref()
+            }
+            fn extensions_mut(&mut self) -> &mut Map<String, Any> {
+                self.extras.get_or_insert_with(Map::new)
+            }
+        }
+        )+
+    };
+}
+impl_extensions! {
+    OpenAPIV3, PathItem, Operation, Parameter, RequestBody, Response, Tag,
+}
+
+impl Extensible for Schema {
+    fn extensions(&self) -> Option<&Map<String, Any>> {
+        Some(&self.extras)
+    }
+    fn extensions_mut(&mut self) -> &mut Map<String, Any> {
+        &mut self.extras
+    }
 }
 
 macro_rules! impl_serde_json {
@@ -525,15 +2672,24 @@ macro_rules! impl_serde_json {
             pub fn to_value(&self) -> serde_json::Value {
                 serde_json::to_value(&self).unwrap()
             }
+            /// Like `to_string`, returning the error instead of panicking.
+            pub fn try_to_string(&self) -> Result<String, serde_json::Error> {
+                serde_json::to_string(&self)
+            }
+            /// Like `to_value`, returning the error instead of panicking.
+            pub fn try_to_value(&self) -> Result<serde_json::Value, serde_json::Error> {
+                serde_json::to_value(&self)
+            }
         }
         )+
     };
 }
 impl_serde_json! {
     OpenAPIV3, Info, Contact, License, Server, ServerVariable, Components, PathItem,
-    Operation, ExternalDocumentation, ParameterIn, Parameter, RequestBody, MediaType,
+    Operation, ExternalDocumentation, ParameterIn, ParameterStyle, Parameter, RequestBody, MediaType,
     Encoding, Responses, Response, Callback, Example, Link, Header, Tag, Reference,
-    Schema, Discriminator, SecurityType, SecurityScheme, OauthFlows, OauthFlow, SecurityRequirement,
+    Schema, Discriminator, Xml, ApiKeyLocation, SecurityType, SecurityScheme, OauthFlows, OauthFlow,
+    SecurityRequirement,
 }
 
 #[cfg(test)]
@@ -562,4 +2718,1631 @@ mod test {
             pass! { OpenAPIV3, include_str!("../examples/v3.0/json/uspto.json") }
         }
     }
+
+    mod builder {
+        use crate::*;
+        use serde_json::json;
+
+        #[test]
+        fn should_build_link_with_operation_ref_only() {
+            let link = Link::new()
+                .with_operation_ref("#/paths/~12.0~1repositories~1{username}/get")
+                .add_parameter("username", json!("$response.body#/username"))
+                .with_description("the repositories owned by the user");
+            assert_eq!(
+                link.to_value(),
+                json!({
+                    "operationRef": "#/paths/~12.0~1repositories~1{username}/get",
+                    "parameters": {"username": "$response.body#/username"},
+                    "description": "the repositories owned by the user"
+                })
+            );
+        }
+
+        #[test]
+        fn should_prefer_reference_over_sibling_keys() {
+            let schema: Referenceable<Schema> = serde_json::from_value(json!({
+                "$ref": "#/components/schemas/Pet",
+                "description": "ignored next to $ref",
+                "type": "object"
+            }))
+            .unwrap();
+            assert_eq!(
+                schema
+                    .as_reference()
+                    .map(|reference| reference._ref.as_str()),
+                Some("#/components/schemas/Pet")
+            );
+
+            let parameter: Referenceable<Parameter> = serde_json::from_value(json!({
+                "$ref": "#/components/parameters/limit",
+                "name": "limit",
+                "in": "query"
+            }))
+            .unwrap();
+            assert!(parameter.is_reference());
+        }
+
+        #[test]
+        fn should_build_callback_with_single_expression() {
+            let on_data: Operation = serde_json::from_value(json!({
+                "requestBody": {"content": {"application/json": {"schema": {"type": "object"}}}},
+                "responses": {"202": {"description": "accepted"}}
+            }))
+            .unwrap();
+            let subscribe: Operation = serde_json::from_value(
+                json!({"responses": {"201": {"description": "subscribed"}}}),
+            )
+            .unwrap();
+            let callback = Callback::new().add_expression(
+                "{$request.body#/callbackUrl}",
+                PathItem {
+                    post: Some(on_data),
+                    ..Default::default()
+                },
+            );
+            let operation = subscribe.add_callback("onData", Referenceable::Data(callback));
+            let value = operation.to_value();
+            assert_eq!(
+                value["callbacks"]["onData"]["{$request.body#/callbackUrl}"]["post"]["responses"]
+                    ["202"]["description"],
+                "accepted"
+            );
+            let parsed: Operation = serde_json::from_value(value.clone()).unwrap();
+            assert_eq!(parsed.to_value(), value);
+        }
+
+        #[test]
+        fn should_populate_all_component_maps() {
+            let parameter: Parameter =
+                serde_json::from_value(json!({"name": "limit", "in": "query"})).unwrap();
+            let security_scheme: SecurityScheme =
+                serde_json::from_value(json!({"type": "http", "scheme": "bearer"})).unwrap();
+            let request_body: RequestBody =
+                serde_json::from_value(json!({"content": {"application/json": {}}})).unwrap();
+            let components = Components::new()
+                .add_schema("User", Referenceable::Data(Schema::default()))
+                .add_response("NotFound", Referenceable::Data(Response::new("not found")))
+                .add_parameter("limit", Referenceable::Data(parameter))
+                .add_example("user", Referenceable::Data(Example::default()))
+                .add_request_body("NewUser", Referenceable::Data(request_body))
+                .add_header("X-Rate-Limit", Referenceable::Data(Header::default()))
+                .add_security_scheme("bearer", Referenceable::Data(security_scheme))
+                .add_link(
+                    "self",
+                    Referenceable::Data(Link::new().with_operation_id("getUser")),
+                )
+                .add_callback(
+                    "onEvent",
+                    Referenceable::Reference(Reference {
+                        _ref: "#/components/callbacks/event".to_string(),
+                    }),
+                );
+            let value = components.to_value();
+            for key in [
+                "schemas",
+                "responses",
+                "parameters",
+                "examples",
+                "requestBodies",
+                "headers",
+                "securitySchemes",
+                "links",
+                "callbacks",
+            ] {
+                assert_eq!(value[key].as_object().unwrap().len(), 1, "{}", key);
+            }
+        }
+
+        #[test]
+        fn should_build_multipart_media_type_with_encoding() {
+            let mut encoding = Map::new();
+            encoding.insert(
+                "profileImage".to_string(),
+                Encoding {
+                    content_type: Some("image/png, image/jpeg".to_string()),
+                    ..Default::default()
+                },
+            );
+            let media_type = MediaType::new()
+                .with_schema(Referenceable::Data(Schema {
+                    _type: Some("object".to_string()),
+                    ..Default::default()
+                }))
+                .add_example(
+                    "avatar",
+                    Referenceable::Data(Example {
+                        summary: Some("an avatar upload".to_string()),
+                        ..Default::default()
+                    }),
+                )
+                .with_encoding(encoding);
+            assert_eq!(
+                media_type.to_value(),
+                json!({
+                    "schema": {"type": "object"},
+                    "examples": {"avatar": {"summary": "an avatar upload"}},
+                    "encoding": {"profileImage": {"contentType": "image/png, image/jpeg"}}
+                })
+            );
+        }
+
+        #[test]
+        fn should_compare_parameter_location_by_value() {
+            let parameter: Parameter =
+                serde_json::from_value(json!({"name": "petId", "in": "path", "required": true}))
+                    .unwrap();
+            assert_eq!(parameter._in, ParameterIn::Path);
+            let location = parameter._in;
+            let mut by_location = std::collections::HashMap::new();
+            by_location.insert(location, vec![parameter.name.clone()]);
+            assert_eq!(by_location[&ParameterIn::Path], vec!["petId".to_string()]);
+        }
+
+        #[test]
+        fn should_compare_operations_by_value() {
+            let build = || {
+                crate::builders::get("find pet by id")
+                    .operation_id("getPetById")
+                    .tag("pet")
+                    .build()
+            };
+            assert_eq!(build(), build());
+            assert_ne!(build(), crate::builders::get("find pets").build());
+        }
+
+        #[test]
+        fn should_parse_mutual_tls_scheme() {
+            let scheme: SecurityScheme = serde_json::from_value(
+                json!({"type": "mutualTLS", "description": "client certificate"}),
+            )
+            .unwrap();
+            assert_eq!(scheme._type, SecurityType::MutualTls);
+            assert_eq!(
+                scheme.to_value(),
+                json!({"type": "mutualTLS", "description": "client certificate"})
+            );
+            let http: SecurityScheme =
+                serde_json::from_value(json!({"type": "http", "scheme": "basic"})).unwrap();
+            assert!(matches!(http._type, SecurityType::Http { .. }));
+        }
+
+        #[test]
+        fn should_reject_api_key_in_path() {
+            let header: SecurityScheme = serde_json::from_value(
+                json!({"type": "apiKey", "name": "X-API-Key", "in": "header"}),
+            )
+            .unwrap();
+            match header._type {
+                SecurityType::ApiKey { _in, .. } => {
+                    assert_eq!(ParameterIn::from(_in), ParameterIn::Header)
+                }
+                _ => panic!("expected an apiKey scheme"),
+            }
+            let path = serde_json::from_value::<SecurityScheme>(
+                json!({"type": "apiKey", "name": "key", "in": "path"}),
+            );
+            assert!(path.is_err());
+        }
+
+        #[test]
+        fn should_round_trip_operation_extensions() {
+            let raw = json!({
+                "operationId": "purgeCache",
+                "responses": {"204": {"description": "purged"}},
+                "x-internal": true
+            });
+            let operation: Operation = serde_json::from_value(raw.clone()).unwrap();
+            assert_eq!(operation.get_extension::<bool>("x-internal"), Some(true));
+            assert_eq!(operation.to_value(), raw);
+
+            let built = crate::builders::delete("purge the cache")
+                .build()
+                .add_extension("x-internal", json!(true));
+            assert_eq!(built.to_value()["x-internal"], json!(true));
+        }
+
+        #[test]
+        fn should_store_typed_extension() {
+            #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+            struct RateLimit {
+                requests: u32,
+                window: String,
+            }
+            let limit = RateLimit {
+                requests: 100,
+                window: "1m".to_string(),
+            };
+            let mut operation = crate::builders::get("list pets").build();
+            operation.set_extension("rate-limit", &limit).unwrap();
+            assert_eq!(
+                operation.to_value()["x-rate-limit"],
+                json!({"requests": 100, "window": "1m"})
+            );
+            assert_eq!(
+                operation.get_extension::<RateLimit>("rate-limit"),
+                Some(limit)
+            );
+            assert_eq!(operation.get_extension::<bool>("x-rate-limit"), None);
+            assert_eq!(operation.get_extension::<RateLimit>("x-missing"), None);
+
+            let by_status = BTreeMap::from([((200, "ok"), 1)]);
+            assert!(operation.set_extension("by-status", by_status).is_err());
+            assert_eq!(operation.get_extension::<Any>("by-status"), None);
+        }
+
+        fn pet_reference() -> Referenceable<Schema> {
+            Referenceable::Reference(Reference {
+                _ref: "#/components/schemas/Pet".to_string(),
+            })
+        }
+
+        #[test]
+        fn should_access_referenceable_data() {
+            let mut data = Referenceable::Data(Schema::default());
+            assert!(data.is_data());
+            assert!(data.as_data().is_some());
+            data.data_mut().unwrap()._type = Some("string".to_string());
+            assert_eq!(
+                data.clone().into_data().unwrap()._type.as_deref(),
+                Some("string")
+            );
+            assert_eq!(data.unwrap_data()._type.as_deref(), Some("string"));
+
+            let mut reference = pet_reference();
+            assert!(reference.is_reference());
+            assert!(reference.data_mut().is_none());
+            assert_eq!(
+                reference.as_reference().unwrap()._ref,
+                "#/components/schemas/Pet"
+            );
+            assert!(reference.into_data().is_none());
+        }
+
+        #[test]
+        #[should_panic(
+            expected = "called `Referenceable::unwrap_data()` on a reference to `#/components/schemas/Pet`"
+        )]
+        fn should_panic_when_unwrapping_reference() {
+            pet_reference().unwrap_data();
+        }
+
+        #[test]
+        fn should_build_component_refs() {
+            fn reference<T>(referenceable: Referenceable<T>) -> String {
+                referenceable.as_reference().unwrap()._ref.clone()
+            }
+            assert_eq!(
+                reference(Referenceable::<Schema>::schema_ref("Pet")),
+                "#/components/schemas/Pet"
+            );
+            assert_eq!(
+                reference(Referenceable::<Response>::response_ref("NotFound")),
+                "#/components/responses/NotFound"
+            );
+            assert_eq!(
+                reference(Referenceable::<Parameter>::parameter_ref("limit")),
+                "#/components/parameters/limit"
+            );
+            assert_eq!(
+                reference(Referenceable::<RequestBody>::request_body_ref("NewPet")),
+                "#/components/requestBodies/NewPet"
+            );
+            assert_eq!(
+                reference(Referenceable::<Example>::example_ref("cat")),
+                "#/components/examples/cat"
+            );
+            assert_eq!(
+                reference(Referenceable::<Header>::header_ref("X-Rate-Limit")),
+                "#/components/headers/X-Rate-Limit"
+            );
+            assert_eq!(
+                reference(Referenceable::<Link>::link_ref("owner")),
+                "#/components/links/owner"
+            );
+            assert_eq!(
+                reference(Referenceable::<Callback>::callback_ref("onEvent")),
+                "#/components/callbacks/onEvent"
+            );
+            assert_eq!(
+                reference(Referenceable::<SecurityScheme>::security_scheme_ref(
+                    "bearer"
+                )),
+                "#/components/securitySchemes/bearer"
+            );
+        }
+
+        #[test]
+        fn should_generate_example_value_for_object() {
+            let schema: Schema = serde_json::from_value(json!({
+                "type": "object",
+                "properties": {
+                    "id": {"type": "integer", "format": "int64"},
+                    "name": {"type": "string", "example": "doggie"},
+                    "tags": {"type": "array", "items": {"type": "string"}},
+                    "photos": {
+                        "type": "array",
+                        "items": {"type": "object", "properties": {"url": {"type": "string"}}}
+                    },
+                    "category": {"$ref": "#/components/schemas/Category"}
+                }
+            }))
+            .unwrap();
+            assert_eq!(
+                schema.example_value(),
+                json!({
+                    "id": 0,
+                    "name": "doggie",
+                    "tags": ["string"],
+                    "photos": [{"url": "string"}],
+                    "category": null
+                })
+            );
+        }
+
+        #[test]
+        fn should_generate_example_value_from_enum() {
+            let status: Schema = serde_json::from_value(
+                json!({"type": "string", "enum": ["available", "pending", "sold"]}),
+            )
+            .unwrap();
+            assert_eq!(status.example_value(), json!("available"));
+            let defaulted: Schema =
+                serde_json::from_value(json!({"type": "boolean", "default": false})).unwrap();
+            assert_eq!(defaulted.example_value(), json!(false));
+        }
+
+        #[test]
+        fn should_expand_server_url() {
+            let server: Server = serde_json::from_value(json!({
+                "url": "https://{region}.api.example.com:{port}/v1",
+                "variables": {
+                    "region": {"default": "eu", "enum": ["eu", "us"]},
+                    "port": {"default": "443"}
+                }
+            }))
+            .unwrap();
+            assert_eq!(
+                server.expand(&BTreeMap::new()).unwrap(),
+                "https://eu.api.example.com:443/v1"
+            );
+            let mut overrides = BTreeMap::new();
+            overrides.insert("region".to_string(), "us".to_string());
+            overrides.insert("port".to_string(), "8443".to_string());
+            assert_eq!(
+                server.expand(&overrides).unwrap(),
+                "https://us.api.example.com:8443/v1"
+            );
+            overrides.insert("region".to_string(), "apac".to_string());
+            assert_eq!(
+                server.expand(&overrides),
+                Err(ExpandError::NotInEnum {
+                    variable: "region".to_string(),
+                    value: "apac".to_string()
+                })
+            );
+
+            let unknown: Server =
+                serde_json::from_value(json!({"url": "https://{tenant}.example.com"})).unwrap();
+            assert_eq!(
+                unknown.expand(&BTreeMap::new()),
+                Err(ExpandError::UnknownVariable("tenant".to_string()))
+            );
+        }
+
+        #[test]
+        fn should_validate_server_variable_default_against_enum() {
+            let region = ServerVariable::new("eu")
+                .with_enum(vec!["eu".to_string(), "us".to_string()])
+                .unwrap();
+            assert_eq!(region.validate(), Ok(()));
+            let outside = ServerVariable::new("apac")
+                .with_enum(vec!["eu".to_string(), "us".to_string()])
+                .unwrap();
+            assert_eq!(
+                outside.validate(),
+                Err(
+                    "default value `apac` is not one of the enum values [\"eu\", \"us\"]"
+                        .to_string()
+                )
+            );
+            let empty: ServerVariable =
+                serde_json::from_value(json!({"default": "eu", "enum": []})).unwrap();
+            assert!(empty.validate().is_err());
+            assert_eq!(ServerVariable::new("443").validate(), Ok(()));
+        }
+
+        #[test]
+        fn should_reject_empty_server_variable_enum() {
+            assert_eq!(
+                ServerVariable::new("eu").with_enum(vec![]),
+                Err("the enum of a server variable should not be empty".to_string())
+            );
+        }
+
+        #[test]
+        fn should_round_trip_parameter_style() {
+            let raw = json!({"name": "ids", "in": "query", "style": "form", "explode": false});
+            let parameter: Parameter = serde_json::from_value(raw.clone()).unwrap();
+            assert_eq!(parameter.style, Some(ParameterStyle::Form));
+            assert_eq!(parameter.to_value(), raw);
+
+            let deep: Parameter = serde_json::from_value(
+                json!({"name": "filter", "in": "query", "style": "deepObject"}),
+            )
+            .unwrap();
+            assert_eq!(deep.style, Some(ParameterStyle::DeepObject));
+
+            let odd: Parameter =
+                serde_json::from_value(json!({"name": "id", "in": "query", "style": "frm"}))
+                    .unwrap();
+            assert_eq!(odd.style, Some(ParameterStyle::Other("frm".to_string())));
+            assert_eq!(odd.to_value()["style"], "frm");
+        }
+
+        #[test]
+        fn should_default_style_by_location() {
+            let parameter = |location: &str| -> Parameter {
+                serde_json::from_value(json!({"name": "id", "in": location})).unwrap()
+            };
+            assert_eq!(parameter("query").effective_style(), ParameterStyle::Form);
+            assert_eq!(parameter("cookie").effective_style(), ParameterStyle::Form);
+            assert_eq!(parameter("path").effective_style(), ParameterStyle::Simple);
+            assert_eq!(
+                parameter("header").effective_style(),
+                ParameterStyle::Simple
+            );
+        }
+
+        #[test]
+        fn should_match_response_by_status_range() {
+            let responses: Responses = serde_json::from_value(json!({
+                "200": {"description": "ok"},
+                "2XX": {"description": "other success"},
+                "4XX": {"description": "client error"},
+                "5xx": {"description": "lowercase range"},
+                "6XX": {"description": "undefined range"},
+                "default": {"description": "unexpected"}
+            }))
+            .unwrap();
+            let description = |status| {
+                responses
+                    .response_for_status(status)
+                    .and_then(|response| response.as_data())
+                    .map(|response| response.description.as_str())
+            };
+            assert_eq!(description(200), Some("ok"));
+            assert_eq!(description(204), Some("other success"));
+            assert_eq!(description(404), Some("client error"));
+            assert_eq!(description(500), Some("unexpected"));
+            assert_eq!(description(2000), Some("unexpected"));
+            assert_eq!(description(600), Some("unexpected"));
+            assert_eq!(Responses::default().response_for_status(500), None);
+        }
+
+        #[test]
+        fn should_collect_responses_with_default() {
+            let responses: Responses = vec![
+                ("200".to_string(), Referenceable::Data(Response::new("ok"))),
+                (
+                    "default".to_string(),
+                    Referenceable::Data(Response::new("unexpected")),
+                ),
+                ("404".to_string(), Referenceable::response_ref("NotFound")),
+            ]
+            .into_iter()
+            .collect();
+            assert_eq!(responses.data.len(), 2);
+            assert!(!responses.data.contains_key("default"));
+            assert_eq!(
+                responses.default,
+                Some(Referenceable::Data(Response::new("unexpected")))
+            );
+            assert_eq!(
+                Responses::from_pairs([("200", Referenceable::Data(Response::new("ok")))]),
+                Responses::new().with_status("200", Referenceable::Data(Response::new("ok")))
+            );
+        }
+
+        #[test]
+        fn should_default_to_empty_document() {
+            let spec = OpenAPIV3::default();
+            assert_eq!(spec.openapi, "3.0.0");
+            assert_eq!(spec, OpenAPIV3::new(Info::new("", "")));
+            assert!(spec.paths.is_empty());
+            assert_eq!(spec.components, None);
+        }
+
+        #[test]
+        fn should_partition_success_and_error_responses() {
+            let operation = Operation {
+                responses: Responses::from_pairs([
+                    ("200", Referenceable::Data(Response::new("ok"))),
+                    ("2XX", Referenceable::Data(Response::new("other success"))),
+                    ("404", Referenceable::response_ref("NotFound")),
+                    ("5xx", Referenceable::Data(Response::new("server error"))),
+                    ("default", Referenceable::Data(Response::new("unexpected"))),
+                ]),
+                ..Operation::default()
+            };
+            let success: Vec<&str> = operation
+                .success_responses()
+                .map(|(code, _)| code)
+                .collect();
+            let mut errors: Vec<&str> = operation.error_responses().map(|(code, _)| code).collect();
+            errors.sort();
+            assert_eq!(success.len(), 2);
+            assert!(success.contains(&"200") && success.contains(&"2XX"));
+            assert_eq!(errors, vec!["404", "5xx"]);
+            assert!(operation.responses.default.is_some());
+        }
+
+        #[test]
+        fn should_add_server_variables_one_by_one() {
+            let server = Server::new("https://{region}.example.com/{version}")
+                .add_variable(
+                    "region",
+                    ServerVariable::new("eu")
+                        .with_enum(vec!["eu".to_string(), "us".to_string()])
+                        .unwrap(),
+                )
+                .add_variable("version", ServerVariable::new("v1"));
+            let spec = OpenAPIV3::default().add_server(server.clone());
+
+            assert_eq!(server.variables.as_ref().map(Map::len), Some(2));
+            assert_eq!(
+                server.expand(&Default::default()),
+                Ok("https://eu.example.com/v1".to_string())
+            );
+            assert_eq!(spec.servers, Some(vec![server]));
+        }
+
+        #[test]
+        fn should_remove_paths_without_operations() {
+            let mut spec: OpenAPIV3 =
+                serde_json::from_str(include_str!("../examples/v3.0/json/petstore.json")).unwrap();
+            let pets = spec.get_path_mut("/pets").unwrap();
+            pets.get = None;
+            pets.post = None;
+            assert!(pets.is_empty());
+            spec = spec.add_path(
+                "/external",
+                PathItem {
+                    _ref: Some("other.yaml#/paths/~1external".to_string()),
+                    ..PathItem::default()
+                },
+            );
+
+            spec.remove_empty_paths();
+            assert!(!spec.has_path("/pets"));
+            assert!(spec.has_path("/pets/{petId}"));
+            assert!(spec.has_path("/external"));
+        }
+
+        #[test]
+        fn should_find_undeclared_tags() {
+            let spec: OpenAPIV3 =
+                serde_json::from_str(include_str!("../examples/v3.0/json/petstore.json")).unwrap();
+            let pets = BTreeSet::from(["pets".to_string()]);
+            assert_eq!(spec.used_tags(), pets);
+            assert_eq!(spec.undeclared_tags(), pets);
+
+            let spec = spec.add_tag(Tag::new("pets", None));
+            assert!(spec.undeclared_tags().is_empty());
+        }
+
+        #[test]
+        fn should_collect_media_types() {
+            let spec: OpenAPIV3 =
+                serde_json::from_str(include_str!("../examples/v3.0/json/petstore-expanded.json"))
+                    .unwrap();
+            assert_eq!(
+                spec.media_types(),
+                BTreeSet::from(["application/json".to_string()])
+            );
+
+            let spec: OpenAPIV3 =
+                serde_json::from_str(include_str!("../examples/v3.0/json/uspto.json")).unwrap();
+            assert_eq!(
+                spec.media_types(),
+                BTreeSet::from([
+                    "application/json".to_string(),
+                    "application/x-www-form-urlencoded".to_string()
+                ])
+            );
+
+            let spec: OpenAPIV3 = serde_json::from_value(json!({
+                "openapi": "3.0.3",
+                "info": {"title": "uploads", "version": "1.0"},
+                "paths": {
+                    "/uploads": {
+                        "post": {
+                            "parameters": [{"name": "filter", "in": "query", "content": {"application/vnd.filter+json": {}}}],
+                            "requestBody": {"content": {"multipart/form-data": {
+                                "encoding": {"image": {"contentType": "image/png, image/jpeg"}}
+                            }}},
+                            "responses": {"201": {
+                                "description": "created",
+                                "headers": {"X-Meta": {"content": {"text/plain": {}}}}
+                            }}
+                        }
+                    }
+                }
+            }))
+            .unwrap();
+            assert_eq!(
+                spec.media_types(),
+                BTreeSet::from([
+                    "application/vnd.filter+json".to_string(),
+                    "image/jpeg".to_string(),
+                    "image/png".to_string(),
+                    "multipart/form-data".to_string(),
+                    "text/plain".to_string(),
+                ])
+            );
+        }
+
+        #[test]
+        fn should_list_deprecated_operations() {
+            let mut spec: OpenAPIV3 =
+                serde_json::from_str(include_str!("../examples/v3.0/json/petstore.json")).unwrap();
+            assert!(spec.deprecated_operations().is_empty());
+            let post = spec.get_path_mut("/pets").unwrap().post.as_mut().unwrap();
+            post.deprecated = Some(true);
+            assert!(post.is_deprecated());
+            assert_eq!(
+                spec.deprecated_operations(),
+                vec![("/pets".to_string(), HttpMethod::Post)]
+            );
+        }
+
+        #[test]
+        fn should_add_response_to_existing_operation() {
+            let mut spec: OpenAPIV3 =
+                serde_json::from_str(include_str!("../examples/v3.0/json/petstore.json")).unwrap();
+            let post = spec.get_path_mut("/pets").unwrap().post.as_mut().unwrap();
+            post.add_response("500", Referenceable::response_ref("ServerError"));
+            post.add_parameter(Referenceable::parameter_ref("TraceId"));
+
+            let post = spec.paths["/pets"].post.as_ref().unwrap();
+            assert_eq!(
+                post.responses.data.get("500"),
+                Some(&Referenceable::response_ref("ServerError"))
+            );
+            assert!(post.responses.data.contains_key("201"));
+            assert_eq!(
+                post.parameters,
+                Some(vec![Referenceable::parameter_ref("TraceId")])
+            );
+        }
+
+        #[test]
+        fn should_add_common_response_where_missing() {
+            let mut spec: OpenAPIV3 =
+                serde_json::from_str(include_str!("../examples/v3.0/json/petstore.json")).unwrap();
+            let created = spec.paths["/pets"].post.as_ref().unwrap().responses.data["201"].clone();
+            spec.add_common_response("201", Referenceable::response_ref("Created"));
+            spec.add_common_response("429", Referenceable::response_ref("TooManyRequests"));
+
+            for (_, _, operation) in spec.operations() {
+                assert_eq!(
+                    operation.responses.data.get("429"),
+                    Some(&Referenceable::response_ref("TooManyRequests"))
+                );
+            }
+            let post = spec.paths["/pets"].post.as_ref().unwrap();
+            assert_eq!(post.responses.data["201"], created);
+        }
+
+        #[test]
+        fn should_convert_between_parameter_and_header() {
+            let parameter = Parameter::new("X-Request-Id", ParameterIn::Header)
+                .with_description("correlates logs")
+                .with_required(true)
+                .with_schema(Referenceable::schema_ref("RequestId"));
+            let header = Header::from_parameter(&parameter);
+            assert_eq!(header.schema, Some(Referenceable::schema_ref("RequestId")));
+            assert_eq!(header.required, Some(true));
+            assert_eq!(header.description.as_deref(), Some("correlates logs"));
+
+            assert_eq!(
+                Parameter::from_header("X-Request-Id", ParameterIn::Header, &header),
+                parameter
+            );
+        }
+
+        #[test]
+        fn should_round_trip_license_identifier() {
+            let license = License::new("MIT").with_identifier("MIT");
+            assert_eq!(
+                license.to_value(),
+                json!({"name": "MIT", "identifier": "MIT"})
+            );
+            assert_eq!(
+                serde_json::from_value::<License>(license.to_value()).unwrap(),
+                license
+            );
+            assert_eq!(License::new("MIT").to_value(), json!({"name": "MIT"}));
+        }
+
+        #[test]
+        fn should_register_security_scheme() {
+            let mut spec = OpenAPIV3::default();
+            spec.add_security_scheme(
+                "bearer",
+                Referenceable::Data(SecurityScheme {
+                    _type: SecurityType::Http {
+                        scheme: "bearer".to_string(),
+                        bearer_format: Some("JWT".to_string()),
+                    },
+                    description: None,
+                }),
+            )
+            .add_security(SecurityRequirement {
+                data: Map::from_iter([("bearer".to_string(), vec![])]),
+            });
+
+            let value = spec.to_value();
+            assert_eq!(
+                value["components"]["securitySchemes"]["bearer"],
+                json!({"type": "http", "scheme": "bearer", "bearerFormat": "JWT"})
+            );
+            assert_eq!(value["security"], json!([{"bearer": []}]));
+        }
+
+        #[test]
+        fn should_collect_oauth_scopes() {
+            let mut spec: OpenAPIV3 = serde_json::from_value(json!({
+                "openapi": "3.0.0",
+                "info": {"title": "scopes", "version": "1"},
+                "paths": {
+                    "/pets": {"get": {
+                        "security": [{"petstore": ["admin:pets"]}, {"apiKey": []}],
+                        "responses": {"200": {"description": "ok"}}
+                    }}
+                },
+                "components": {"securitySchemes": {
+                    "petstore": {"type": "oauth2", "flows": {
+                        "implicit": {
+                            "authorizationUrl": "https://example.com/auth",
+                            "scopes": {"read:pets": "read"}
+                        },
+                        "authorizationCode": {
+                            "authorizationUrl": "https://example.com/auth",
+                            "tokenUrl": "https://example.com/token",
+                            "scopes": {"read:pets": "read", "write:pets": "write"}
+                        }
+                    }},
+                    "apiKey": {"type": "apiKey", "name": "key", "in": "header"}
+                }}
+            }))
+            .unwrap();
+            spec.add_security(SecurityRequirement {
+                data: Map::from_iter([("petstore".to_string(), vec!["read:pets".to_string()])]),
+            });
+
+            let scopes = spec.all_oauth_scopes();
+            assert_eq!(scopes.len(), 1);
+            assert_eq!(
+                scopes["petstore"],
+                BTreeSet::from(["admin:pets", "read:pets", "write:pets"].map(String::from))
+            );
+        }
+
+        #[test]
+        fn should_validate_oauth_flow_urls() {
+            let flows: OauthFlows = serde_json::from_value(json!({
+                "authorizationCode": {
+                    "authorizationUrl": "https://example.com/auth",
+                    "scopes": {}
+                },
+                "clientCredentials": {
+                    "tokenUrl": "https://example.com/token",
+                    "scopes": {}
+                }
+            }))
+            .unwrap();
+            assert_eq!(
+                flows.validate(),
+                vec!["authorizationCode flow is missing tokenUrl".to_string()]
+            );
+
+            let flows: OauthFlows = serde_json::from_value(json!({
+                "implicit": {"authorizationUrl": "https://example.com/auth", "scopes": {}}
+            }))
+            .unwrap();
+            assert!(flows.validate().is_empty());
+        }
+
+        #[test]
+        fn should_collect_extensions_with_locations() {
+            let mut spec: OpenAPIV3 =
+                serde_json::from_str(include_str!("../examples/v3.0/json/petstore.json")).unwrap();
+            let pets = spec.get_path_mut("/pets").unwrap();
+            pets.get
+                .as_mut()
+                .unwrap()
+                .set_extension("internal", true)
+                .unwrap();
+            pets.post
+                .as_mut()
+                .unwrap()
+                .set_extension("x-internal", false)
+                .unwrap();
+            let mut spec = spec.add_extension("x-logo", json!("logo.png"));
+            if let Some(Referenceable::Data(pet)) = spec
+                .components
+                .as_mut()
+                .and_then(|components| components.schemas.as_mut())
+                .and_then(|schemas| schemas.get_mut("Pet"))
+            {
+                pet.set_extension("internal", true).unwrap();
+            }
+            let create = spec.get_path_mut("/pets").unwrap().post.as_mut().unwrap();
+            create.request_body = Some(Referenceable::Data(
+                serde_json::from_value(json!({
+                    "content": {"application/json": {"schema": {
+                        "type": "object",
+                        "properties": {"name": {"type": "string", "x-internal": true}}
+                    }}}
+                }))
+                .unwrap(),
+            ));
+
+            let extensions = spec.all_extensions();
+            assert_eq!(extensions.len(), 2);
+            assert_eq!(
+                extensions["x-logo"],
+                vec![("#".to_string(), json!("logo.png"))]
+            );
+            assert_eq!(
+                extensions["x-internal"],
+                vec![
+                    ("#/paths/~1pets/get".to_string(), json!(true)),
+                    ("#/paths/~1pets/post".to_string(), json!(false)),
+                    (
+                        "#/paths/~1pets/post/requestBody/content/application~1json/schema/properties/name"
+                            .to_string(),
+                        json!(true)
+                    ),
+                    ("#/components/schemas/Pet".to_string(), json!(true)),
+                ]
+            );
+        }
+
+        #[test]
+        fn should_serialize_schema_extras_in_stable_order() {
+            let schema: Schema = serde_json::from_str(
+                r#"{"x-b": 1, "oneOf": [], "x-a": 2, "description": "d", "allOf": [], "type": "object", "format": "f"}"#,
+            )
+            .unwrap();
+            let json = schema.to_string();
+            let keys = [
+                "type",
+                "format",
+                "description",
+                "allOf",
+                "oneOf",
+                "x-a",
+                "x-b",
+            ];
+            let positions: Vec<usize> = keys
+                .iter()
+                .map(|key| json.find(&format!("\"{}\"", key)).unwrap())
+                .collect();
+            assert!(
+                positions.windows(2).all(|pair| pair[0] < pair[1]),
+                "{}",
+                json
+            );
+            assert_eq!(serde_json::from_str::<Schema>(&json).unwrap(), schema);
+        }
+
+        #[test]
+        fn should_collect_components_from_schemas() {
+            let components: Components = vec![
+                ("Pet".to_string(), Referenceable::Data(Schema::default())),
+                ("Pets".to_string(), Referenceable::schema_ref("Pet")),
+            ]
+            .into_iter()
+            .collect();
+            assert_eq!(components.schemas.as_ref().map(Map::len), Some(2));
+            assert_eq!(components.responses, None);
+            assert_eq!(
+                Components::from_schemas([
+                    ("Pet", Referenceable::Data(Schema::default())),
+                    ("Pets", Referenceable::schema_ref("Pet")),
+                ]),
+                components
+            );
+            assert_eq!(
+                Components::new()
+                    .add_schema("Pet", Referenceable::Data(Schema::default()))
+                    .add_schema("Pets", Referenceable::schema_ref("Pet")),
+                components
+            );
+        }
+
+        #[test]
+        fn should_parse_from_slice() {
+            let bytes = include_bytes!("../examples/v3.0/json/uspto.json");
+            let spec = OpenAPIV3::from_slice(bytes).unwrap();
+            assert_eq!(spec.info.title, "USPTO Data Set API");
+            assert_eq!(
+                spec.to_value(),
+                serde_json::from_slice::<serde_json::Value>(bytes).unwrap()
+            );
+            assert!(OpenAPIV3::from_slice(b"{").is_err());
+        }
+
+        #[test]
+        fn should_override_default_security_with_public_operation() {
+            let bearer = SecurityRequirement {
+                data: Map::from_iter([("bearer".to_string(), vec![])]),
+            };
+            let health = Operation::default().public();
+            let spec = OpenAPIV3::new(Info::new("api", "1.0"))
+                .with_default_security(vec![bearer])
+                .add_path(
+                    "/health",
+                    PathItem {
+                        get: Some(health),
+                        ..Default::default()
+                    },
+                );
+
+            let value = spec.to_value();
+            assert_eq!(value["security"], json!([{"bearer": []}]));
+            assert_eq!(value["paths"]["/health"]["get"]["security"], json!([]));
+        }
+
+        #[test]
+        fn should_merge_path_and_operation_parameters() {
+            let components = Components::new().add_parameter(
+                "Limit",
+                Referenceable::Data(Parameter::new("limit", ParameterIn::Query)),
+            );
+            let item: PathItem = serde_json::from_value(json!({
+                "parameters": [
+                    {"name": "id", "in": "path", "required": true},
+                    {"$ref": "#/components/parameters/Limit"},
+                    {"name": "limit", "in": "header"}
+                ],
+                "get": {
+                    "parameters": [
+                        {"name": "limit", "in": "query", "schema": {"type": "integer", "maximum": 50}},
+                        {"name": "offset", "in": "query"}
+                    ],
+                    "responses": {"200": {"description": "ok"}}
+                }
+            }))
+            .unwrap();
+
+            let parameters = item.effective_parameters(item.get.as_ref().unwrap(), &components);
+            let names: Vec<(&str, ParameterIn)> = parameters
+                .iter()
+                .map(|parameter| {
+                    let parameter = parameter.resolve(&components).unwrap();
+                    (parameter.name.as_str(), parameter._in)
+                })
+                .collect();
+            assert_eq!(
+                names,
+                vec![
+                    ("id", ParameterIn::Path),
+                    ("limit", ParameterIn::Header),
+                    ("limit", ParameterIn::Query),
+                    ("offset", ParameterIn::Query),
+                ]
+            );
+            assert!(parameters[2].as_data().unwrap().schema.is_some());
+        }
+
+        #[test]
+        fn should_parse_http_methods() {
+            assert_eq!("get".parse(), Ok(HttpMethod::Get));
+            assert_eq!("PATCH".parse(), Ok(HttpMethod::Patch));
+            assert_eq!(HttpMethod::Delete.to_string(), "delete");
+            assert_eq!(
+                "fetch".parse::<HttpMethod>(),
+                Err(ParseMethodError("fetch".to_string()))
+            );
+
+            let item =
+                PathItem::default().with_operations([(HttpMethod::Get, Operation::default())]);
+            assert!(item.get.is_some());
+            let item = PathItem::default()
+                .with_operations_str([("POST", Operation::default())])
+                .unwrap();
+            assert!(item.post.is_some());
+            assert!(PathItem::default()
+                .with_operations_str([
+                    ("get", Operation::default()),
+                    ("fetch", Operation::default())
+                ])
+                .is_err());
+        }
+
+        #[test]
+        fn should_mark_request_body_required() {
+            let body = Referenceable::json_body(Referenceable::schema_ref("Pet")).required(true);
+            assert_eq!(
+                serde_json::to_value(&body).unwrap(),
+                json!({
+                    "required": true,
+                    "content": {"application/json": {"schema": {"$ref": "#/components/schemas/Pet"}}}
+                })
+            );
+            assert_eq!(
+                Referenceable::request_body_ref("NewPet").required(true),
+                Referenceable::request_body_ref("NewPet")
+            );
+            let parameter = Referenceable::Data(Parameter::new("limit", ParameterIn::Query));
+            assert_eq!(
+                parameter.with_required(true).as_data().unwrap().required,
+                Some(true)
+            );
+        }
+
+        #[test]
+        fn should_round_trip_schema_xml() {
+            let value = json!({
+                "type": "array",
+                "items": {"type": "string", "xml": {"name": "tag"}},
+                "xml": {"name": "user", "wrapped": true}
+            });
+            let schema: Schema = serde_json::from_value(value.clone()).unwrap();
+            let xml = schema.xml.as_ref().unwrap();
+            assert_eq!(xml.name.as_deref(), Some("user"));
+            assert_eq!(xml.wrapped, Some(true));
+            assert!(schema.extras.is_empty());
+            assert_eq!(schema.to_value(), value);
+
+            let built = Schema::default().with_xml(Xml {
+                name: Some("user".to_string()),
+                wrapped: Some(true),
+                ..Default::default()
+            });
+            assert_eq!(
+                built.to_value(),
+                json!({"xml": {"name": "user", "wrapped": true}})
+            );
+        }
+
+        #[test]
+        fn should_round_trip_schema_external_docs() {
+            let value = json!({
+                "type": "object",
+                "externalDocs": {"url": "https://example.com/docs/user", "description": "the user model"}
+            });
+            let schema: Schema = serde_json::from_value(value.clone()).unwrap();
+            assert_eq!(
+                schema.external_docs.as_ref().unwrap().url,
+                "https://example.com/docs/user"
+            );
+            assert!(schema.extras.is_empty());
+            assert_eq!(schema.to_value(), value);
+
+            let docs: ExternalDocumentation =
+                serde_json::from_value(json!({"url": "https://example.com"})).unwrap();
+            let built = Schema::default().with_external_docs(docs);
+            assert_eq!(
+                built.to_value(),
+                json!({"externalDocs": {"url": "https://example.com"}})
+            );
+        }
+
+        #[test]
+        fn should_build_and_read_constant_schema() {
+            let schema = Schema::constant(json!("dog"));
+            assert_eq!(schema.to_value(), json!({"enum": ["dog"]}));
+            assert_eq!(schema.is_constant(), Some(&json!("dog")));
+
+            let parsed: Schema = serde_json::from_value(json!({"enum": ["dog", "cat"]})).unwrap();
+            assert_eq!(parsed.is_constant(), None);
+            assert_eq!(Schema::default().is_constant(), None);
+        }
+
+        #[test]
+        fn should_walk_properties_through_all_of() {
+            let components: Components = serde_json::from_value(json!({
+                "schemas": {
+                    "Base": {
+                        "properties": {
+                            "id": {"type": "integer"},
+                            "audit": {"properties": {"createdAt": {"type": "string"}}}
+                        }
+                    },
+                    "Node": {
+                        "properties": {"children": {"type": "array", "items": {"$ref": "#/components/schemas/Node"}},
+                                       "parent": {"$ref": "#/components/schemas/Node"}}
+                    }
+                }
+            }))
+            .unwrap();
+            let schema: Schema = serde_json::from_value(json!({
+                "properties": {"tree": {"$ref": "#/components/schemas/Node"}},
+                "allOf": [
+                    {"$ref": "#/components/schemas/Base"},
+                    {"properties": {"name": {"type": "string"}}}
+                ]
+            }))
+            .unwrap();
+
+            let mut visited = vec![];
+            schema.walk_properties(&components, &mut |path, _| visited.push(path.to_string()));
+            assert_eq!(
+                visited,
+                vec![
+                    "tree",
+                    "tree.children",
+                    "tree.parent",
+                    "audit",
+                    "audit.createdAt",
+                    "id",
+                    "name",
+                ]
+            );
+        }
+
+        #[test]
+        fn should_measure_schema_depth() {
+            let components: Components = serde_json::from_value(json!({
+                "schemas": {
+                    "Address": {"properties": {"city": {"type": "string"}}},
+                    "Node": {"properties": {"next": {"$ref": "#/components/schemas/Node"}}}
+                }
+            }))
+            .unwrap();
+            let flat: Schema = serde_json::from_value(json!({"type": "string"})).unwrap();
+            assert_eq!(flat.depth(&components), 1);
+
+            let nested: Schema = serde_json::from_value(json!({
+                "properties": {
+                    "name": {"type": "string"},
+                    "address": {"$ref": "#/components/schemas/Address"}
+                }
+            }))
+            .unwrap();
+            assert_eq!(nested.depth(&components), 3);
+            let wrapped: Schema = serde_json::from_value(json!({
+                "type": "array",
+                "items": {"allOf": [{"$ref": "#/components/schemas/Address"}]}
+            }))
+            .unwrap();
+            assert_eq!(wrapped.depth(&components), 4);
+
+            let node = components.schemas.as_ref().unwrap()["Node"]
+                .as_data()
+                .unwrap();
+            assert_eq!(node.depth(&components), usize::MAX);
+        }
+
+        #[test]
+        fn should_answer_schema_type_predicates() {
+            let schema = |value| serde_json::from_value::<Schema>(value).unwrap();
+
+            let object = schema(json!({"type": "object"}));
+            assert!(object.is_object() && !object.is_primitive() && !object.is_array());
+            assert!(schema(json!({"properties": {"id": {"type": "integer"}}})).is_object());
+            assert!(!Schema::default().is_object());
+
+            let array = schema(json!({"type": "array", "items": {"type": "string"}}));
+            assert!(array.is_array() && !array.is_object() && !array.is_primitive());
+
+            let string = schema(json!({"type": "string", "nullable": true}));
+            assert!(string.is_string() && string.is_primitive() && string.is_nullable());
+            for _type in ["number", "integer", "boolean"] {
+                let primitive = schema(json!({"type": _type}));
+                assert!(primitive.is_primitive() && !primitive.is_string());
+                assert!(!primitive.is_nullable());
+            }
+            assert!(!schema(json!({"nullable": false})).is_nullable());
+        }
+
+        #[test]
+        fn should_warn_about_operations_next_to_path_item_ref() {
+            let item: PathItem = serde_json::from_value(json!({
+                "$ref": "./paths/users.yaml",
+                "summary": "users",
+                "get": {"responses": {"200": {"description": "ok"}}}
+            }))
+            .unwrap();
+            assert!(item.is_reference());
+            assert_eq!(
+                item.validate(),
+                vec!["the get operation is ignored because the path item refers to `./paths/users.yaml`"]
+            );
+
+            let plain: PathItem = serde_json::from_value(json!({
+                "get": {"responses": {"200": {"description": "ok"}}}
+            }))
+            .unwrap();
+            assert!(!plain.is_reference());
+            assert!(plain.validate().is_empty());
+        }
+
+        #[test]
+        fn should_emit_explicit_default_server() {
+            let spec = OpenAPIV3::new(Info::new("api", "1.0"));
+            assert!(spec.to_value().get("servers").is_none());
+            assert_eq!(
+                spec.with_explicit_default_server().to_value()["servers"],
+                json!([{"url": "/"}])
+            );
+
+            let custom = OpenAPIV3::new(Info::new("api", "1.0"))
+                .add_server(Server::new("https://api.example.com"))
+                .with_explicit_default_server();
+            assert_eq!(
+                custom.to_value()["servers"],
+                json!([{"url": "https://api.example.com"}])
+            );
+
+            let later = OpenAPIV3::new(Info::new("api", "1.0"))
+                .with_explicit_default_server()
+                .add_server(Server::new("https://api.example.com"));
+            assert_eq!(
+                later.to_value()["servers"],
+                json!([{"url": "https://api.example.com"}])
+            );
+            let emptied = OpenAPIV3 {
+                servers: Some(vec![]),
+                ..later
+            };
+            assert_eq!(emptied.to_value()["servers"], json!([{"url": "/"}]));
+        }
+
+        #[test]
+        fn should_find_success_responses_without_content() {
+            let spec: OpenAPIV3 = serde_json::from_value(json!({
+                "openapi": "3.0.0",
+                "info": {"title": "pets", "version": "1.0.0"},
+                "paths": {
+                    "/pets": {
+                        "get": {"responses": {
+                            "200": {"description": "the pets"},
+                            "404": {"description": "no pets"}
+                        }},
+                        "post": {"responses": {
+                            "201": {"$ref": "#/components/responses/Created"}
+                        }}
+                    },
+                    "/pets/{id}": {
+                        "get": {"responses": {"200": {"description": "a pet", "content": {"application/json": {}}}}},
+                        "delete": {"responses": {"204": {"description": "deleted"}}}
+                    }
+                },
+                "components": {"responses": {"Created": {"description": "created"}}}
+            }))
+            .unwrap();
+            assert_eq!(
+                spec.responses_without_content(),
+                vec![
+                    ("/pets".to_string(), HttpMethod::Get, "200".to_string()),
+                    ("/pets".to_string(), HttpMethod::Post, "201".to_string()),
+                ]
+            );
+        }
+
+        #[test]
+        fn should_look_up_responses_by_key() {
+            let responses = Responses::from_pairs([
+                ("200", Referenceable::Data(Response::new("ok"))),
+                ("4XX", Referenceable::Data(Response::new("client error"))),
+                ("default", Referenceable::response_ref("Error")),
+            ]);
+            assert_eq!(
+                responses.get("200").unwrap().as_data().unwrap().description,
+                "ok"
+            );
+            assert_eq!(
+                responses.get("default"),
+                Some(&Referenceable::response_ref("Error"))
+            );
+            assert_eq!(responses.get("404"), None);
+            assert!(responses.contains("4XX"));
+            assert!(!responses.contains("500"));
+            assert!(!Responses::new().contains("default"));
+            assert_eq!(
+                responses
+                    .iter()
+                    .map(|(status, _)| status)
+                    .collect::<Vec<_>>(),
+                vec!["200", "4XX", "default"]
+            );
+        }
+
+        #[test]
+        fn should_only_accept_supported_openapi_versions() {
+            let mut spec = OpenAPIV3::default();
+            assert_eq!(spec.set_openapi_version("3.0.3"), Ok(()));
+            assert_eq!(spec.openapi, "3.0.3");
+
+            assert_eq!(
+                spec.set_openapi_version("banana"),
+                Err(VersionError::Invalid("banana".to_string()))
+            );
+            assert_eq!(
+                spec.set_openapi_version("3.1.0"),
+                Err(VersionError::Unsupported("3.1.0".to_string()))
+            );
+            assert!(spec.set_openapi_version("3.0").is_err());
+            assert_eq!(spec.openapi, "3.0.3");
+        }
+
+        #[test]
+        fn should_count_operations_per_tag() {
+            let spec: OpenAPIV3 = serde_json::from_value(json!({
+                "openapi": "3.0.0",
+                "info": {"title": "shop", "version": "1.0.0"},
+                "paths": {
+                    "/users": {
+                        "get": {"tags": ["users"], "responses": {"200": {"description": "ok"}}},
+                        "post": {"tags": ["users", "admin"], "responses": {"201": {"description": "ok"}}}
+                    },
+                    "/health": {
+                        "get": {"responses": {"200": {"description": "ok"}}}
+                    }
+                }
+            }))
+            .unwrap();
+            assert_eq!(
+                spec.operations_per_tag(),
+                BTreeMap::from([
+                    ("".to_string(), 1),
+                    ("admin".to_string(), 1),
+                    ("users".to_string(), 2),
+                ])
+            );
+        }
+
+        #[test]
+        fn should_add_several_tags_and_security_requirements() {
+            let requirement = |scheme: &str| SecurityRequirement {
+                data: Map::from_iter([(scheme.to_string(), vec![])]),
+            };
+            let mut spec = OpenAPIV3::new(Info::new("api", "1.0")).add_tag(Tag::new("users", None));
+            spec.add_tags(vec![
+                Tag::new("pets", None),
+                Tag::new("orders", None),
+                Tag::new("store", Some("the store".to_string())),
+            ])
+            .add_security_requirements(vec![requirement("bearer"), requirement("apiKey")]);
+            let names: Vec<&str> = spec
+                .tags
+                .iter()
+                .flatten()
+                .map(|tag| tag.name.as_str())
+                .collect();
+            assert_eq!(names, vec!["users", "pets", "orders", "store"]);
+            assert_eq!(spec.security.unwrap().len(), 2);
+        }
+
+        #[test]
+        fn should_merge_path_items() {
+            let mut users: PathItem = serde_json::from_value(json!({
+                "parameters": [{"name": "X-Tenant", "in": "header"}],
+                "get": {"operationId": "listUsers", "responses": {"200": {"description": "ok"}}}
+            }))
+            .unwrap();
+            let create: PathItem = serde_json::from_value(json!({
+                "summary": "users",
+                "parameters": [{"name": "X-Tenant", "in": "header"}, {"name": "dryRun", "in": "query"}],
+                "post": {"operationId": "createUser", "responses": {"201": {"description": "created"}}}
+            }))
+            .unwrap();
+
+            users.merge(create).unwrap();
+            assert_eq!(
+                users.get.as_ref().unwrap().operation_id.as_deref(),
+                Some("listUsers")
+            );
+            assert_eq!(
+                users.post.as_ref().unwrap().operation_id.as_deref(),
+                Some("createUser")
+            );
+            assert_eq!(users.summary.as_deref(), Some("users"));
+            assert_eq!(users.parameters.as_ref().unwrap().len(), 2);
+
+            let retyped: PathItem = serde_json::from_value(json!({
+                "parameters": [{"name": "dryRun", "in": "query", "schema": {"type": "boolean"}}],
+                "x-owner": "accounts"
+            }))
+            .unwrap();
+            users.merge(retyped).unwrap();
+            let parameters = users.parameters.as_ref().unwrap();
+            assert_eq!(parameters.len(), 2);
+            assert!(parameters[1].as_data().unwrap().schema.is_none());
+            assert_eq!(users.extras.as_ref().unwrap()["x-owner"], json!("accounts"));
+
+            let conflicting: PathItem = serde_json::from_value(json!({
+                "get": {"operationId": "searchUsers", "responses": {"200": {"description": "ok"}}},
+                "delete": {"responses": {"204": {"description": "deleted"}}}
+            }))
+            .unwrap();
+            let before = users.clone();
+            assert_eq!(
+                users.merge(conflicting),
+                Err(MergeConflict {
+                    method: HttpMethod::Get
+                })
+            );
+            assert_eq!(users, before);
+        }
+
+        #[test]
+        fn should_parse_metadata_only() {
+            let spec = json!({
+                "openapi": "3.0.3",
+                "info": {"title": "catalog", "version": "1.0"},
+                "tags": [{"name": "pets"}],
+                "paths": {"/pets": {"get": "not an operation"}},
+                "components": {"schemas": {"Pet": {"type": "object"}}}
+            })
+            .to_string();
+            assert!(serde_json::from_str::<OpenAPIV3>(&spec).is_err());
+
+            let metadata = OpenAPIV3::parse_metadata_only(&spec).unwrap();
+            assert_eq!(metadata.info.title, "catalog");
+            assert_eq!(metadata.tags.unwrap()[0].name, "pets");
+            assert!(metadata
+                .components
+                .unwrap()
+                .schemas
+                .unwrap()
+                .contains_key("Pet"));
+        }
+
+        #[test]
+        fn should_coerce_strings_when_parsing_leniently() {
+            let spec = json!({
+                "openapi": "3.0.3",
+                "info": {"title": "messy", "version": "1.0"},
+                "paths": {"/pets": {"get": {
+                    "parameters": [{"name": "limit", "in": "query", "required": "true", "schema": {"type": "integer", "minimum": "0", "example": "0"}}],
+                    "responses": {"200": {"description": "ok"}}
+                }}}
+            })
+            .to_string();
+            assert!(serde_json::from_str::<OpenAPIV3>(&spec).is_err());
+
+            let parsed = OpenAPIV3::parse_lenient(&spec).unwrap();
+            let operation = parsed.paths["/pets"].get.as_ref().unwrap();
+            let Referenceable::Data(parameter) = &operation.parameters.as_ref().unwrap()[0] else {
+                panic!("inline parameter expected");
+            };
+            assert_eq!(parameter.required, Some(true));
+            let Some(Referenceable::Data(schema)) = &parameter.schema else {
+                panic!("inline schema expected");
+            };
+            assert_eq!(schema.minimum, Some(0.into()));
+            assert_eq!(schema.example, Some(json!("0")));
+
+            let spec = json!({
+                "openapi": "3.0.3",
+                "info": {"title": "messy", "version": "1.0"},
+                "paths": {"/pets": {"get": {
+                    "responses": {"default": {
+                        "description": "error",
+                        "headers": {"X-Rate-Limit": {"required": "true", "schema": {"type": "integer"}}}
+                    }}
+                }}}
+            })
+            .to_string();
+            let parsed = OpenAPIV3::parse_lenient(&spec).unwrap();
+            let responses = &parsed.paths["/pets"].get.as_ref().unwrap().responses;
+            let Some(Referenceable::Data(response)) = responses.get("default") else {
+                panic!("inline default response expected");
+            };
+            let Referenceable::Data(header) = &response.headers.as_ref().unwrap()["X-Rate-Limit"]
+            else {
+                panic!("inline header expected");
+            };
+            assert_eq!(header.required, Some(true));
+        }
+
+        #[test]
+        fn should_serialize_without_panicking() {
+            let spec: OpenAPIV3 =
+                serde_json::from_str(include_str!("../examples/v3.0/json/petstore.json")).unwrap();
+            assert_eq!(spec.try_to_string().unwrap(), spec.to_string());
+            assert_eq!(spec.try_to_value().unwrap(), spec.to_value());
+        }
+
+        #[test]
+        fn should_pick_schema_by_media_type() {
+            let response: Response = serde_json::from_value(json!({
+                "description": "ok",
+                "content": {
+                    "application/json": {"schema": {"$ref": "#/components/schemas/Pet"}},
+                    "application/*": {"schema": {"type": "string", "format": "binary"}},
+                    "text/plain": {"schema": {"type": "string"}}
+                }
+            }))
+            .unwrap();
+            assert_eq!(
+                response.schema_for("application/json; charset=utf-8"),
+                Some(&Referenceable::schema_ref("Pet"))
+            );
+            let Some(Referenceable::Data(binary)) = response.schema_for("application/xml") else {
+                panic!("the application/* schema expected");
+            };
+            assert_eq!(binary.format.as_deref(), Some("binary"));
+            assert!(response.schema_for("image/png").is_none());
+
+            let body: RequestBody = serde_json::from_value(json!({
+                "content": {"application/json": {"schema": {"type": "object"}}}
+            }))
+            .unwrap();
+            assert!(body.schema_for("application/*").is_some());
+            assert!(body.schema_for("text/*").is_none());
+        }
+
+        #[test]
+        fn should_omit_empty_components() {
+            let mut spec = OpenAPIV3::new(Info::new("empty", "1.0"));
+            spec.components = Some(Components::new().with_schemas(Map::new()));
+            assert!(spec.components.as_ref().unwrap().is_empty());
+            assert!(spec.to_value().get("components").is_none());
+
+            spec.components = Some(Components::new().with_schemas(Map::from_iter([(
+                "Pet".to_string(),
+                Referenceable::Data(Schema::default()),
+            )])));
+            assert!(!spec.components.as_ref().unwrap().is_empty());
+            assert!(spec.to_value()["components"]["schemas"]["Pet"].is_object());
+        }
+
+        #[test]
+        fn should_follow_escaped_pointer() {
+            let spec: OpenAPIV3 =
+                serde_json::from_str(include_str!("../examples/v3.0/json/petstore.json")).unwrap();
+            assert_eq!(
+                spec.pointer("/paths/~1pets~1{petId}/get/operationId"),
+                Some(json!("showPetById"))
+            );
+            assert_eq!(
+                spec.pointer("#/paths/~1pets/post/responses/201"),
+                Some(json!({"description": "Null response"}))
+            );
+            assert_eq!(spec.pointer("/paths/pets"), None);
+        }
+
+        #[test]
+        fn should_look_up_and_remove_paths() {
+            let mut spec = OpenAPIV3::default()
+                .add_path("/public", PathItem::default())
+                .add_paths([("/internal", PathItem::default())]);
+            assert!(spec.has_path("/internal"));
+            assert_eq!(spec.get_path("/public"), Some(&PathItem::default()));
+            spec.get_path_mut("/public").unwrap().summary = Some("public".to_string());
+
+            assert_eq!(spec.remove_path("/internal"), Some(PathItem::default()));
+            assert!(!spec.has_path("/internal"));
+            assert_eq!(spec.remove_path("/internal"), None);
+            assert_eq!(spec.paths.len(), 1);
+        }
+
+        #[cfg(feature = "indexmap")]
+        #[test]
+        fn should_preserve_path_order() {
+            let raw = r#"{
+                "openapi": "3.0.0",
+                "info": {"title": "ordered", "version": "1.0.0"},
+                "paths": {
+                    "/zebras": {},
+                    "/apples": {},
+                    "/mangoes": {}
+                }
+            }"#;
+            let spec: OpenAPIV3 = serde_json::from_str(raw).unwrap();
+            let paths: Vec<&str> = spec.paths.keys().map(String::as_str).collect();
+            assert_eq!(paths, vec!["/zebras", "/apples", "/mangoes"]);
+            let serialized = spec.to_string();
+            let zebras = serialized.find("/zebras").unwrap();
+            let apples = serialized.find("/apples").unwrap();
+            assert!(zebras < apples);
+        }
+    }
 }
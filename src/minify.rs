@@ -0,0 +1,164 @@
+//! Stripping documentation from a document.
+
+use crate::visit::{walk_mut, VisitorMut};
+use crate::{
+    Any, ExternalDocumentation, Header, HttpMethod, Info, Link, MediaType, OpenAPIV3, Operation,
+    Parameter, PathItem, RequestBody, Response, Schema, SecurityScheme, Server, Tag,
+};
+
+impl OpenAPIV3 {
+    /// Remove every `description`, `summary`, `example` and `examples` throughout the document, including components and nested schemas.
+    ///
+    /// `Response.description` is required, so it is emptied rather than removed. `components.examples` goes away with the fields that could reference it.
+    pub fn minify(&mut self) {
+        walk_mut(self, &mut Minifier);
+        if let Some(components) = &mut self.components {
+            components.examples = None;
+        }
+    }
+}
+
+struct Minifier;
+
+impl VisitorMut for Minifier {
+    fn visit_info(&mut self, info: &mut Info) {
+        info.description = None;
+    }
+
+    fn visit_server(&mut self, server: &mut Server) {
+        server.description = None;
+        for variable in server
+            .variables
+            .iter_mut()
+            .flat_map(|variables| variables.values_mut())
+        {
+            variable.description = None;
+        }
+    }
+
+    fn visit_path_item(&mut self, _path: &str, item: &mut PathItem) {
+        item.summary = None;
+        item.description = None;
+    }
+
+    fn visit_operation(&mut self, _path: &str, _method: HttpMethod, operation: &mut Operation) {
+        operation.summary = None;
+        operation.description = None;
+    }
+
+    fn visit_parameter(&mut self, parameter: &mut Parameter) {
+        parameter.description = None;
+        parameter.example = None;
+        parameter.examples = None;
+    }
+
+    fn visit_header(&mut self, header: &mut Header) {
+        header.description = None;
+        header.example = None;
+        header.examples = None;
+    }
+
+    fn visit_request_body(&mut self, request_body: &mut RequestBody) {
+        request_body.description = None;
+    }
+
+    fn visit_media_type(&mut self, _content_type: &str, media_type: &mut MediaType) {
+        media_type.example = None;
+        media_type.examples = None;
+    }
+
+    fn visit_response(&mut self, response: &mut Response) {
+        response.description.clear();
+    }
+
+    fn visit_link(&mut self, link: &mut Link) {
+        link.description = None;
+    }
+
+    fn visit_security_scheme(&mut self, scheme: &mut SecurityScheme) {
+        scheme.description = None;
+    }
+
+    fn visit_tag(&mut self, tag: &mut Tag) {
+        tag.description = None;
+    }
+
+    fn visit_external_documentation(&mut self, docs: &mut ExternalDocumentation) {
+        docs.description = None;
+    }
+
+    fn visit_schema(&mut self, schema: &mut Schema) {
+        schema.description = None;
+        schema.example = None;
+        for (keyword, value) in schema.extras.iter_mut() {
+            minify_keyword(keyword, value);
+        }
+        schema.extras.retain(|keyword, _| keyword != "examples");
+    }
+}
+
+/// Minify the subschemas held by an untyped schema keyword, leaving data such as `default` or `enum` alone.
+fn minify_keyword(keyword: &str, value: &mut Any) {
+    match (keyword, value) {
+        ("allOf" | "oneOf" | "anyOf", Any::Array(schemas)) => {
+            schemas.iter_mut().for_each(minify_schema_value)
+        }
+        ("not" | "additionalProperties" | "items", schema) => minify_schema_value(schema),
+        ("properties", Any::Object(properties)) => {
+            properties.values_mut().for_each(minify_schema_value)
+        }
+        _ => {}
+    }
+}
+
+fn minify_schema_value(schema: &mut Any) {
+    if let Any::Object(schema) = schema {
+        schema.retain(|keyword, _| {
+            !matches!(keyword.as_str(), "description" | "example" | "examples")
+        });
+        for (keyword, value) in schema.iter_mut() {
+            minify_keyword(keyword, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_strip_documentation_and_keep_structure() {
+        let mut spec: OpenAPIV3 =
+            serde_json::from_str(include_str!("../examples/v3.0/json/api-with-examples.json"))
+                .unwrap();
+        spec.components = Some(Components::new().add_schema(
+            "Pet",
+            serde_json::from_value(json!({
+                "description": "a pet",
+                "example": {"name": "rex"},
+                "properties": {"description": {"type": "string", "description": "free text"}},
+                "allOf": [{"description": "nested", "properties": {"example": {"type": "string"}}}]
+            }))
+            .unwrap(),
+        ));
+        let before = spec.to_string().len();
+        let operations = spec.operations().count();
+
+        spec.minify();
+        let json = spec.to_string();
+        assert!(json.len() < before / 2);
+        assert_eq!(spec.operations().count(), operations);
+        for (_, _, operation) in spec.operations() {
+            assert!(operation.summary.is_none());
+            assert!(!operation.responses.data.is_empty());
+        }
+        assert_eq!(
+            serde_json::to_value(&spec.components.unwrap().schemas.unwrap()["Pet"]).unwrap(),
+            json!({
+                "properties": {"description": {"type": "string"}},
+                "allOf": [{"properties": {"example": {"type": "string"}}}]
+            })
+        );
+    }
+}
@@ -0,0 +1,437 @@
+//! Walking every `$ref` string of a document, and what can be learnt from them.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+#[cfg(feature = "fs")]
+use std::path::PathBuf;
+
+use crate::{
+    Any, Callback, Component, Components, Encoding, Header, Map, MediaType, OpenAPIV3, Operation,
+    Parameter, PathItem, Reference, Referenceable, RequestBody, Response, Responses, Schema,
+};
+
+/// Why the references of a document could not be resolved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolveError {
+    /// the referenced file does not exist or can not be read
+    #[cfg(feature = "fs")]
+    Missing { reference: String, path: PathBuf },
+    /// the referenced file is neither valid JSON nor valid YAML
+    #[cfg(feature = "fs")]
+    Parse { path: PathBuf, message: String },
+    /// the fragment of the reference points at nothing
+    NotFound(String),
+    /// the referenced value is not a valid component of the expected kind
+    Invalid { reference: String, message: String },
+    /// references only lead to each other and never to a definition
+    Cycle(Vec<String>),
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "fs")]
+            ResolveError::Missing { reference, path } => {
+                write!(
+                    f,
+                    "can not read `{}` referenced by `{}`",
+                    path.display(),
+                    reference
+                )
+            }
+            #[cfg(feature = "fs")]
+            ResolveError::Parse { path, message } => {
+                write!(f, "can not parse `{}`: {}", path.display(), message)
+            }
+            ResolveError::NotFound(reference) => write!(f, "`{}` points at nothing", reference),
+            ResolveError::Invalid { reference, message } => {
+                write!(f, "`{}` is not a valid component: {}", reference, message)
+            }
+            ResolveError::Cycle(references) => {
+                write!(f, "reference cycle: {}", references.join(" -> "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for ResolveError {}
+
+/// The callback of a walk, given the component kind the reference should point to, e.g. `schemas`, if known.
+pub(crate) type RefVisitor<'a> = dyn FnMut(Option<&'static str>, &mut String) + 'a;
+
+/// Visit every `$ref` string reachable from a value, including the ones hidden in untyped extensions.
+pub(crate) trait WalkRefs {
+    fn walk_refs(&mut self, f: &mut RefVisitor);
+}
+
+impl<T: WalkRefs> WalkRefs for Option<T> {
+    fn walk_refs(&mut self, f: &mut RefVisitor) {
+        if let Some(value) = self {
+            value.walk_refs(f);
+        }
+    }
+}
+
+impl<T: WalkRefs> WalkRefs for Vec<T> {
+    fn walk_refs(&mut self, f: &mut RefVisitor) {
+        self.iter_mut().for_each(|value| value.walk_refs(f));
+    }
+}
+
+impl<T: WalkRefs> WalkRefs for Box<T> {
+    fn walk_refs(&mut self, f: &mut RefVisitor) {
+        self.as_mut().walk_refs(f);
+    }
+}
+
+impl<T: WalkRefs> WalkRefs for Map<String, T> {
+    fn walk_refs(&mut self, f: &mut RefVisitor) {
+        self.values_mut().for_each(|value| value.walk_refs(f));
+    }
+}
+
+impl<T: WalkRefs + Component> WalkRefs for Referenceable<T> {
+    fn walk_refs(&mut self, f: &mut RefVisitor) {
+        match self {
+            Referenceable::Reference(Reference { _ref }) => f(Some(T::KIND), _ref),
+            Referenceable::Data(data) => data.walk_refs(f),
+        }
+    }
+}
+
+impl WalkRefs for Any {
+    fn walk_refs(&mut self, f: &mut RefVisitor) {
+        walk_any(None, self, f);
+    }
+}
+
+/// References in untyped JSON, where `kind` is the kind of all of them if known.
+fn walk_any(kind: Option<&'static str>, value: &mut Any, f: &mut RefVisitor) {
+    match value {
+        Any::Object(object) => {
+            for (key, value) in object.iter_mut() {
+                match value {
+                    Any::String(reference) if key == "$ref" => f(kind, reference),
+                    value => walk_any(kind, value, f),
+                }
+            }
+        }
+        Any::Array(values) => values.iter_mut().for_each(|value| walk_any(kind, value, f)),
+        _ => {}
+    }
+}
+
+/// Types that hold no references at all.
+macro_rules! impl_walk_refs_leaf {
+    ($($t:ty),+) => {
+        $(
+        impl WalkRefs for $t {
+            fn walk_refs(&mut self, _f: &mut RefVisitor) {}
+        }
+        )+
+    };
+}
+impl_walk_refs_leaf!(crate::Example, crate::Link, crate::SecurityScheme);
+
+/// Walk the listed fields of a struct in order.
+macro_rules! impl_walk_refs {
+    ($($t:ty => $($field:ident),+;)+) => {
+        $(
+        impl WalkRefs for $t {
+            fn walk_refs(&mut self, f: &mut RefVisitor) {
+                $(self.$field.walk_refs(f);)+
+            }
+        }
+        )+
+    };
+}
+impl_walk_refs! {
+    OpenAPIV3 => paths, components, extras;
+    Components => schemas, responses, parameters, examples, request_bodies, headers, security_schemes, links, callbacks;
+    Operation => parameters, request_body, responses, callbacks, extras;
+    Parameter => schema, examples, content, extras;
+    RequestBody => content, extras;
+    MediaType => schema, examples, encoding;
+    Encoding => headers;
+    Responses => default, data;
+    Response => headers, content, links, extras;
+    Callback => data;
+    Header => schema, examples, content;
+}
+
+impl WalkRefs for Schema {
+    fn walk_refs(&mut self, f: &mut RefVisitor) {
+        self.properties.walk_refs(f);
+        self.items.walk_refs(f);
+        // the untyped keywords of a schema, e.g. `allOf`, only hold schemas
+        for value in self.extras.values_mut() {
+            walk_any(Some(Schema::KIND), value, f);
+        }
+    }
+}
+
+impl WalkRefs for PathItem {
+    fn walk_refs(&mut self, f: &mut RefVisitor) {
+        if let Some(reference) = &mut self._ref {
+            f(None, reference);
+        }
+        for (_, operation) in self.operations_mut() {
+            operation.walk_refs(f);
+        }
+        self.parameters.walk_refs(f);
+        self.extras.walk_refs(f);
+    }
+}
+
+/// Every `$ref` reachable from a value, with the kind of component it should point to if known.
+pub(crate) fn collect_refs<T: WalkRefs + Clone>(value: &T) -> Vec<(Option<&'static str>, String)> {
+    let mut refs = vec![];
+    value
+        .clone()
+        .walk_refs(&mut |kind, reference| refs.push((kind, reference.clone())));
+    refs
+}
+
+/// The kind and name of a local `#/components/{kind}/{name}` reference.
+pub(crate) fn local_component(reference: &str) -> Option<(String, String)> {
+    let (kind, name) = reference.strip_prefix("#/components/")?.split_once('/')?;
+    if name.contains('/') {
+        return None;
+    }
+    Some((kind.to_string(), name.replace("~1", "/").replace("~0", "~")))
+}
+
+/// A component, identified by its kind, e.g. `schemas`, and name.
+type Node = (String, String);
+
+/// The local components each component references, for every component of the document.
+fn component_graph(components: &Components) -> BTreeMap<Node, BTreeSet<Node>> {
+    fn add<T: WalkRefs + Component + Clone>(
+        map: &Option<Map<String, Referenceable<T>>>,
+        graph: &mut BTreeMap<Node, BTreeSet<Node>>,
+    ) {
+        for (name, component) in map.iter().flatten() {
+            let edges = collect_refs(component)
+                .into_iter()
+                .filter_map(|(_, reference)| local_component(&reference))
+                .collect();
+            graph.insert((T::KIND.to_string(), name.clone()), edges);
+        }
+    }
+    let mut graph = BTreeMap::new();
+    add(&components.schemas, &mut graph);
+    add(&components.responses, &mut graph);
+    add(&components.parameters, &mut graph);
+    add(&components.examples, &mut graph);
+    add(&components.request_bodies, &mut graph);
+    add(&components.headers, &mut graph);
+    add(&components.security_schemes, &mut graph);
+    add(&components.links, &mut graph);
+    add(&components.callbacks, &mut graph);
+    graph
+}
+
+/// Which components of one kind are defined and which are used.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KindUsage {
+    pub defined: BTreeSet<String>,
+    /// used by an operation, a security requirement, or another used component
+    pub referenced: BTreeSet<String>,
+    /// defined but never used
+    pub orphans: BTreeSet<String>,
+    /// referenced somewhere in the document but not defined
+    pub dangling: BTreeSet<String>,
+}
+
+/// Component usage of a whole document, per kind of component, e.g. `schemas`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ComponentUsageReport {
+    pub kinds: BTreeMap<String, KindUsage>,
+}
+
+impl ComponentUsageReport {
+    /// The usage of one kind, empty if the document neither defines nor references any.
+    pub fn kind(&self, kind: &str) -> KindUsage {
+        self.kinds.get(kind).cloned().unwrap_or_default()
+    }
+}
+
+impl OpenAPIV3 {
+    /// Every `$ref` string of the document, in paths, components and untyped extensions alike.
+    pub fn all_references(&self) -> Vec<String> {
+        collect_refs(self)
+            .into_iter()
+            .map(|(_, reference)| reference)
+            .collect()
+    }
+
+    /// Which components are defined, used, orphaned or dangling.
+    ///
+    /// A component counts as referenced when it is reachable from the paths or, for security schemes, named by a security requirement. A dangling reference is reported wherever it appears, even inside an orphan.
+    pub fn component_usage(&self) -> ComponentUsageReport {
+        let graph = self
+            .components
+            .as_ref()
+            .map(component_graph)
+            .unwrap_or_default();
+
+        let mut roots: Vec<Node> = self
+            .paths
+            .values()
+            .flat_map(collect_refs)
+            .filter_map(|(_, reference)| local_component(&reference))
+            .collect();
+        let requirements = self.security.iter().flatten().chain(
+            self.operations()
+                .flat_map(|(_, _, operation)| operation.security.iter().flatten()),
+        );
+        for requirement in requirements {
+            let schemes = requirement
+                .data
+                .keys()
+                .map(|name| ("securitySchemes".to_string(), name.clone()));
+            roots.extend(schemes);
+        }
+
+        let mut reached = BTreeSet::new();
+        while let Some(node) = roots.pop() {
+            if reached.insert(node.clone()) {
+                roots.extend(graph.get(&node).into_iter().flatten().cloned());
+            }
+        }
+
+        let mut report = ComponentUsageReport::default();
+        for (kind, name) in graph.keys() {
+            let usage = report.kinds.entry(kind.clone()).or_default();
+            usage.defined.insert(name.clone());
+        }
+        for (kind, name) in reached {
+            let usage = report.kinds.entry(kind).or_default();
+            usage.referenced.insert(name);
+        }
+        let everywhere = self
+            .all_references()
+            .into_iter()
+            .filter_map(|reference| local_component(&reference));
+        for (kind, name) in everywhere {
+            if !graph.contains_key(&(kind.clone(), name.clone())) {
+                report.kinds.entry(kind).or_default().dangling.insert(name);
+            }
+        }
+        for usage in report.kinds.values_mut() {
+            usage.orphans = usage
+                .defined
+                .difference(&usage.referenced)
+                .cloned()
+                .collect();
+        }
+        report
+    }
+
+    /// Cycles among local component references, each as the names of the components in the loop, e.g. `["A", "B"]` for `A -> B -> A`.
+    ///
+    /// Self-referential components, legal for recursive schemas such as trees, are reported as single-name cycles. Each cycle is listed once, starting from its smallest name.
+    pub fn find_reference_cycles(&self) -> Vec<Vec<String>> {
+        fn visit(
+            node: &Node,
+            graph: &BTreeMap<Node, BTreeSet<Node>>,
+            stack: &mut Vec<Node>,
+            done: &mut BTreeSet<Node>,
+            cycles: &mut BTreeSet<Vec<Node>>,
+        ) {
+            if let Some(position) = stack.iter().position(|visiting| visiting == node) {
+                let mut cycle = stack[position..].to_vec();
+                let start = (0..cycle.len()).min_by_key(|&i| &cycle[i]).unwrap_or(0);
+                cycle.rotate_left(start);
+                cycles.insert(cycle);
+                return;
+            }
+            if done.contains(node) {
+                return;
+            }
+            stack.push(node.clone());
+            for next in graph.get(node).into_iter().flatten() {
+                visit(next, graph, stack, done, cycles);
+            }
+            stack.pop();
+            done.insert(node.clone());
+        }
+
+        let Some(components) = &self.components else {
+            return vec![];
+        };
+        let graph = component_graph(components);
+        let mut cycles = BTreeSet::new();
+        let mut done = BTreeSet::new();
+        for node in graph.keys() {
+            visit(node, &graph, &mut vec![], &mut done, &mut cycles);
+        }
+        cycles
+            .into_iter()
+            .map(|cycle| cycle.into_iter().map(|(_, name)| name).collect())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_report_orphan_and_dangling_components() {
+        let mut spec: OpenAPIV3 =
+            serde_json::from_str(include_str!("../examples/v3.0/json/petstore.json")).unwrap();
+        let components = spec.components.take().unwrap();
+        spec.components =
+            Some(components.add_schema("Unused", Referenceable::Data(Schema::default())));
+        spec.get_path_mut("/pets")
+            .unwrap()
+            .post
+            .as_mut()
+            .unwrap()
+            .add_response("404", Referenceable::response_ref("NotFound"));
+
+        let report = spec.component_usage();
+        let schemas = report.kind("schemas");
+        assert_eq!(schemas.defined.len(), 4);
+        assert_eq!(
+            schemas.referenced,
+            ["Error", "Pet", "Pets"].map(String::from).into()
+        );
+        assert_eq!(schemas.orphans, ["Unused".to_string()].into());
+        assert!(schemas.dangling.is_empty());
+        let responses = report.kind("responses");
+        assert_eq!(responses.dangling, ["NotFound".to_string()].into());
+        assert_eq!(spec.all_references().len(), 7);
+    }
+
+    #[test]
+    fn should_find_reference_cycles() {
+        let spec: OpenAPIV3 = serde_json::from_value(json!({
+            "openapi": "3.0.0",
+            "info": {"title": "cycles", "version": "1"},
+            "paths": {},
+            "components": {"schemas": {
+                "A": {"properties": {"b": {"$ref": "#/components/schemas/B"}}},
+                "B": {"type": "array", "items": {"$ref": "#/components/schemas/A"}},
+                "Node": {"properties": {"children": {
+                    "type": "array", "items": {"$ref": "#/components/schemas/Node"}
+                }}},
+                "Leaf": {"allOf": [{"$ref": "#/components/schemas/Node"}]}
+            }}
+        }))
+        .unwrap();
+        assert_eq!(
+            spec.find_reference_cycles(),
+            vec![
+                vec!["A".to_string(), "B".to_string()],
+                vec!["Node".to_string()]
+            ]
+        );
+
+        let petstore: OpenAPIV3 =
+            serde_json::from_str(include_str!("../examples/v3.0/json/petstore.json")).unwrap();
+        assert!(petstore.find_reference_cycles().is_empty());
+    }
+}
@@ -0,0 +1,151 @@
+//! A view of a document whose component schemas are shared behind [`Arc`]s instead of being cloned into every place referencing them.
+//!
+//! References are not replaced: schemas, including the shared ones, still hold their nested `$ref`s, and [`ResolvedSpec::resolve`] turns each into the shared handle when it is looked up.
+
+use std::sync::Arc;
+
+use crate::visit::{walk, Visitor};
+use crate::{Map, OpenAPIV3, Reference, Referenceable, ResolveError, Schema};
+
+/// A document with its component schemas taken out and shared, see [`OpenAPIV3::resolve_shared`].
+///
+/// This is a lookup table from component names to handles, not a tree of resolved schemas: follow a nested reference by passing it to [`ResolvedSpec::resolve`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedSpec {
+    /// The document without `components.schemas`; its schema references point into `schemas`.
+    pub spec: OpenAPIV3,
+    /// Every component schema by name. A component that only refers to another one shares its handle.
+    pub schemas: Map<String, Arc<Schema>>,
+}
+
+impl ResolvedSpec {
+    /// The shared schema a reference points at, or a new handle to an inline schema. References to other kinds of objects or unknown schemas give `None`.
+    pub fn resolve(&self, schema: &Referenceable<Schema>) -> Option<Arc<Schema>> {
+        match schema {
+            Referenceable::Data(data) => Some(Arc::new(data.clone())),
+            Referenceable::Reference(reference) => self
+                .schemas
+                .get(&reference.component_name("schemas")?)
+                .cloned(),
+        }
+    }
+}
+
+impl OpenAPIV3 {
+    /// Move the component schemas behind shared handles, so resolving a reference to the same schema twice gives the same allocation.
+    ///
+    /// Every schema reference of the document, including the ones nested in `allOf` and the like, must point at a component schema, and components only referring to each other are reported as a cycle. Inline schemas and references stay where they are.
+    pub fn resolve_shared(&self) -> Result<ResolvedSpec, ResolveError> {
+        let mut references = SchemaReferences::default();
+        walk(self, &mut references);
+
+        let mut spec = self.clone();
+        let components = spec
+            .components
+            .as_mut()
+            .and_then(|components| components.schemas.take())
+            .unwrap_or_default();
+        let mut schemas = Map::new();
+        for (name, schema) in &components {
+            if let Referenceable::Data(data) = schema {
+                schemas.insert(name.clone(), Arc::new(data.clone()));
+            }
+        }
+        for (name, schema) in &components {
+            let mut seen = vec![];
+            let mut current = schema;
+            let mut target = None;
+            while let Referenceable::Reference(reference) = current {
+                if seen.contains(&reference._ref) {
+                    seen.push(reference._ref.clone());
+                    return Err(ResolveError::Cycle(seen));
+                }
+                seen.push(reference._ref.clone());
+                let name = local_name(reference)
+                    .filter(|name| components.contains_key(name))
+                    .ok_or_else(|| ResolveError::NotFound(reference._ref.clone()))?;
+                current = &components[&name];
+                target = Some(name);
+            }
+            if let Some(target) = target {
+                let shared = Arc::clone(&schemas[&target]);
+                schemas.insert(name.clone(), shared);
+            }
+        }
+
+        for reference in references.0 {
+            if local_name(&reference).is_none_or(|name| !schemas.contains_key(&name)) {
+                return Err(ResolveError::NotFound(reference._ref));
+            }
+        }
+        Ok(ResolvedSpec { spec, schemas })
+    }
+}
+
+fn local_name(reference: &Reference) -> Option<String> {
+    reference.component_name("schemas")
+}
+
+/// Collects every schema reference of a document.
+#[derive(Default)]
+struct SchemaReferences(Vec<Reference>);
+
+impl Visitor for SchemaReferences {
+    fn visit_reference(&mut self, kind: &str, reference: &Reference) {
+        if kind == "schemas" {
+            self.0.push(reference.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_share_referenced_schemas() {
+        let mut spec: OpenAPIV3 =
+            serde_json::from_str(include_str!("../examples/v3.0/json/petstore.json")).unwrap();
+        spec.components
+            .as_mut()
+            .unwrap()
+            .schemas
+            .as_mut()
+            .unwrap()
+            .insert("Animal".to_string(), Referenceable::schema_ref("Pet"));
+        let resolved = spec.resolve_shared().unwrap();
+        assert!(resolved.spec.components.as_ref().unwrap().schemas.is_none());
+
+        let first = resolved.resolve(&Referenceable::schema_ref("Pet")).unwrap();
+        let second = resolved.resolve(&Referenceable::schema_ref("Pet")).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(Arc::ptr_eq(&first, &resolved.schemas["Animal"]));
+
+        let cyclic: OpenAPIV3 = serde_json::from_value(json!({
+            "openapi": "3.0.3",
+            "info": {"title": "cyclic", "version": "1.0"},
+            "paths": {},
+            "components": {"schemas": {"A": {"$ref": "#/components/schemas/B"}, "B": {"$ref": "#/components/schemas/A"}}}
+        }))
+        .unwrap();
+        assert!(matches!(
+            cyclic.resolve_shared(),
+            Err(ResolveError::Cycle(_))
+        ));
+
+        let dangling: OpenAPIV3 = serde_json::from_value(json!({
+            "openapi": "3.0.3",
+            "info": {"title": "dangling", "version": "1.0"},
+            "paths": {},
+            "components": {"schemas": {"Cat": {"allOf": [{"$ref": "#/components/schemas/Pet"}]}}}
+        }))
+        .unwrap();
+        assert_eq!(
+            dangling.resolve_shared(),
+            Err(ResolveError::NotFound(
+                "#/components/schemas/Pet".to_string()
+            ))
+        );
+    }
+}
@@ -0,0 +1,362 @@
+//! Runtime expressions, as used by links and callbacks, e.g. `$request.path.id` or `{$response.body#/url}`.
+
+use crate::{Any, Callback, Link, Map, OpenAPIV3, Operation, Referenceable, Response};
+use std::fmt;
+
+/// A parsed runtime expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuntimeExpression {
+    /// `$url`
+    Url,
+    /// `$method`
+    Method,
+    /// `$statusCode`
+    StatusCode,
+    /// `$request.{source}`
+    Request(Source),
+    /// `$response.{source}`
+    Response(Source),
+}
+
+/// The part of a request or response a runtime expression reads.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// `header.{token}`
+    Header(String),
+    /// `query.{name}`
+    Query(String),
+    /// `path.{name}`
+    Path(String),
+    /// `body`, optionally followed by `#` and a JSON pointer
+    Body(Option<String>),
+}
+
+/// Why a runtime expression is malformed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub expression: String,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid runtime expression `{}`: {}",
+            self.expression, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl RuntimeExpression {
+    /// Parse a bare expression such as `$request.header.X-Id`.
+    pub fn parse(expression: &str) -> Result<RuntimeExpression, ParseError> {
+        let error = |message: &str| ParseError {
+            expression: expression.to_string(),
+            message: message.to_string(),
+        };
+        match expression {
+            "$url" => return Ok(RuntimeExpression::Url),
+            "$method" => return Ok(RuntimeExpression::Method),
+            "$statusCode" => return Ok(RuntimeExpression::StatusCode),
+            _ => {}
+        }
+        let (wrap, source): (fn(Source) -> RuntimeExpression, &str) =
+            if let Some(source) = expression.strip_prefix("$request.") {
+                (RuntimeExpression::Request, source)
+            } else if let Some(source) = expression.strip_prefix("$response.") {
+                (RuntimeExpression::Response, source)
+            } else {
+                return Err(error(
+                    "expected `$url`, `$method`, `$statusCode`, `$request.` or `$response.`",
+                ));
+            };
+
+        let source = if let Some(token) = source.strip_prefix("header.") {
+            if token.is_empty() || !token.chars().all(is_tchar) {
+                return Err(error("a header name must be a non-empty token"));
+            }
+            Source::Header(token.to_string())
+        } else if let Some(name) = source.strip_prefix("query.") {
+            if name.is_empty() {
+                return Err(error("a query parameter name must not be empty"));
+            }
+            Source::Query(name.to_string())
+        } else if let Some(name) = source.strip_prefix("path.") {
+            if name.is_empty() {
+                return Err(error("a path parameter name must not be empty"));
+            }
+            Source::Path(name.to_string())
+        } else if source == "body" {
+            Source::Body(None)
+        } else if let Some(pointer) = source.strip_prefix("body#") {
+            if !is_json_pointer(pointer) {
+                return Err(error("the body fragment must be a JSON pointer"));
+            }
+            Source::Body(Some(pointer.to_string()))
+        } else {
+            return Err(error("expected `header.`, `query.`, `path.` or `body`"));
+        };
+        Ok(wrap(source))
+    }
+
+    /// Parse every `{expression}` embedded in a string such as a callback key, e.g. `{$request.body#/callbackUrl}?id={$request.path.id}`.
+    pub fn parse_template(template: &str) -> Result<Vec<RuntimeExpression>, ParseError> {
+        let mut expressions = vec![];
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            let end = rest[start..].find('}').ok_or_else(|| ParseError {
+                expression: template.to_string(),
+                message: "unterminated `{`".to_string(),
+            })? + start;
+            expressions.push(RuntimeExpression::parse(&rest[start + 1..end])?);
+            rest = &rest[end + 1..];
+        }
+        Ok(expressions)
+    }
+}
+
+impl fmt::Display for RuntimeExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (prefix, source) = match self {
+            RuntimeExpression::Url => return write!(f, "$url"),
+            RuntimeExpression::Method => return write!(f, "$method"),
+            RuntimeExpression::StatusCode => return write!(f, "$statusCode"),
+            RuntimeExpression::Request(source) => ("$request", source),
+            RuntimeExpression::Response(source) => ("$response", source),
+        };
+        match source {
+            Source::Header(token) => write!(f, "{}.header.{}", prefix, token),
+            Source::Query(name) => write!(f, "{}.query.{}", prefix, name),
+            Source::Path(name) => write!(f, "{}.path.{}", prefix, name),
+            Source::Body(None) => write!(f, "{}.body", prefix),
+            Source::Body(Some(pointer)) => write!(f, "{}.body#{}", prefix, pointer),
+        }
+    }
+}
+
+fn is_tchar(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c)
+}
+
+fn is_json_pointer(pointer: &str) -> bool {
+    if !pointer.is_empty() && !pointer.starts_with('/') {
+        return false;
+    }
+    let mut chars = pointer.chars();
+    while let Some(c) = chars.next() {
+        if c == '~' && !matches!(chars.next(), Some('0' | '1')) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Check every runtime expression of the document: link parameters and request bodies starting with `$`, and the `{expression}`s embedded in callback keys.
+///
+/// The operations of callbacks are checked too, including the links and callbacks nested in them. Each error comes with the `#/...` pointer of the link or callback holding it.
+pub fn validate(spec: &OpenAPIV3) -> Vec<(String, ParseError)> {
+    let mut errors = vec![];
+    for (path, item) in &spec.paths {
+        let path = crate::transform::escape(path);
+        for (method, operation) in item.operations() {
+            let location = format!("#/paths/{}/{}", path, method.as_str());
+            validate_operation(&location, operation, &mut errors);
+        }
+    }
+    if let Some(components) = &spec.components {
+        for (name, response) in components.responses.iter().flatten() {
+            let location = format!("#/components/responses/{}", crate::transform::escape(name));
+            validate_response(&location, response, &mut errors);
+        }
+        for (name, link) in components.links.iter().flatten() {
+            let location = format!("#/components/links/{}", crate::transform::escape(name));
+            validate_link(&location, link, &mut errors);
+        }
+        for (name, callback) in components.callbacks.iter().flatten() {
+            let location = format!("#/components/callbacks/{}", crate::transform::escape(name));
+            validate_callback(&location, callback, &mut errors);
+        }
+    }
+    errors
+}
+
+fn validate_operation(
+    location: &str,
+    operation: &Operation,
+    errors: &mut Vec<(String, ParseError)>,
+) {
+    for (status, response) in operation.responses.iter() {
+        let location = format!("{}/responses/{}", location, status);
+        validate_response(&location, response, errors);
+    }
+    for (name, callback) in operation.callbacks.iter().flatten() {
+        let location = format!("{}/callbacks/{}", location, crate::transform::escape(name));
+        validate_callback(&location, callback, errors);
+    }
+}
+
+fn validate_response(
+    location: &str,
+    response: &Referenceable<Response>,
+    errors: &mut Vec<(String, ParseError)>,
+) {
+    if let Referenceable::Data(response) = response {
+        for (name, link) in response.links.iter().flatten() {
+            let location = format!("{}/links/{}", location, crate::transform::escape(name));
+            validate_link(&location, link, errors);
+        }
+    }
+}
+
+fn validate_link(
+    location: &str,
+    link: &Referenceable<Link>,
+    errors: &mut Vec<(String, ParseError)>,
+) {
+    let Referenceable::Data(link) = link else {
+        return;
+    };
+    let values = link
+        .parameters
+        .iter()
+        .flat_map(Map::values)
+        .chain(link.request_body.iter());
+    for value in values {
+        if let Any::String(expression) = value {
+            if expression.starts_with('$') {
+                if let Err(error) = RuntimeExpression::parse(expression) {
+                    errors.push((location.to_string(), error));
+                }
+            }
+        }
+    }
+}
+
+fn validate_callback(
+    location: &str,
+    callback: &Referenceable<Callback>,
+    errors: &mut Vec<(String, ParseError)>,
+) {
+    let Referenceable::Data(callback) = callback else {
+        return;
+    };
+    for (expression, item) in &callback.data {
+        if let Err(error) = RuntimeExpression::parse_template(expression) {
+            errors.push((location.to_string(), error));
+        }
+        let location = format!("{}/{}", location, crate::transform::escape(expression));
+        for (method, operation) in item.operations() {
+            let location = format!("{}/{}", location, method.as_str());
+            validate_operation(&location, operation, errors);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_parse_valid_expressions() {
+        for expression in [
+            "$url",
+            "$method",
+            "$statusCode",
+            "$request.path.id",
+            "$request.query.queryUrl",
+            "$request.header.X-Request-Id",
+            "$request.body",
+            "$response.body#/url",
+            "$response.body#/data~1items/0",
+        ] {
+            let parsed = RuntimeExpression::parse(expression).unwrap();
+            assert_eq!(parsed.to_string(), expression);
+        }
+        assert_eq!(
+            RuntimeExpression::parse("$response.body#/url"),
+            Ok(RuntimeExpression::Response(Source::Body(Some(
+                "/url".to_string()
+            ))))
+        );
+        assert_eq!(
+            RuntimeExpression::parse_template(
+                "http://example.com?id={$request.path.id}&to={$request.body#/email}"
+            )
+            .unwrap()
+            .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn should_reject_malformed_expressions() {
+        for expression in [
+            "request.path.id",
+            "$request",
+            "$request.cookie.id",
+            "$request.path.",
+            "$request.header.X Id",
+            "$response.body/url",
+            "$response.body#url",
+            "$response.body#/a~2",
+        ] {
+            assert!(
+                RuntimeExpression::parse(expression).is_err(),
+                "{}",
+                expression
+            );
+        }
+        assert!(RuntimeExpression::parse_template("{$request.body#/url").is_err());
+    }
+
+    #[test]
+    fn should_validate_links_and_callbacks() {
+        let mut spec: OpenAPIV3 =
+            serde_json::from_str(include_str!("../examples/v3.0/json/link-example.json")).unwrap();
+        assert!(validate(&spec).is_empty());
+        let callback: Callback =
+            serde_json::from_value(json!({"{$request.bdy#/url}": {}})).unwrap();
+        spec.components
+            .get_or_insert_with(Default::default)
+            .callbacks = Some(Map::from_iter([(
+            "hook".to_string(),
+            Referenceable::Data(callback),
+        )]));
+
+        let errors = validate(&spec);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, "#/components/callbacks/hook");
+
+        let nested: Callback = serde_json::from_value(json!({
+            "{$request.body#/url}": {
+                "post": {
+                    "responses": {"200": {
+                        "description": "ok",
+                        "links": {"next": {"operationId": "next", "parameters": {"id": "$response.bdy#/id"}}}
+                    }},
+                    "callbacks": {"inner": {"{$request.query.}": {}}}
+                }
+            }
+        }))
+        .unwrap();
+        spec.components.as_mut().unwrap().callbacks = Some(Map::from_iter([(
+            "hook".to_string(),
+            Referenceable::Data(nested),
+        )]));
+        let locations: Vec<String> = validate(&spec)
+            .into_iter()
+            .map(|(location, _)| location)
+            .collect();
+        assert_eq!(
+            locations,
+            vec![
+                "#/components/callbacks/hook/{$request.body#~1url}/post/responses/200/links/next",
+                "#/components/callbacks/hook/{$request.body#~1url}/post/callbacks/inner",
+            ]
+        );
+    }
+}
@@ -0,0 +1,162 @@
+//! Deriving schemas from Rust types through `schemars`.
+
+use crate::{Any, Components, Map, Referenceable, Schema};
+use schemars::gen::SchemaSettings;
+use schemars::JsonSchema;
+use serde_json::json;
+
+/// The schema of `T`, with every named type it uses added to `components.schemas`.
+///
+/// Named types, including `T` itself when it is a struct or enum, come back as `#/components/schemas/...` references. Definitions already in `components` are replaced. Boolean schemas become `{}` for `true` and `{"not": {}}` for `false`, and a schema that can not be represented is an error, leaving `components` untouched.
+///
+/// ```rust
+/// use oas::{schema_from_type, Components, Referenceable};
+///
+/// #[derive(schemars::JsonSchema)]
+/// struct Pet {
+///     name: String,
+///     tag: Option<String>,
+/// }
+///
+/// let mut components = Components::new();
+/// let pet = schema_from_type::<Pet>(&mut components).unwrap();
+/// assert_eq!(pet, Referenceable::schema_ref("Pet"));
+/// assert!(components.schemas.unwrap().contains_key("Pet"));
+/// ```
+pub fn schema_from_type<T: JsonSchema>(
+    components: &mut Components,
+) -> Result<Referenceable<Schema>, serde_json::Error> {
+    let mut generator = SchemaSettings::openapi3().into_generator();
+    let schema = convert(generator.subschema_for::<T>())?;
+    let definitions = generator
+        .take_definitions()
+        .into_iter()
+        .map(|(name, definition)| Ok((name, convert(definition)?)))
+        .collect::<Result<Vec<_>, serde_json::Error>>()?;
+    if !definitions.is_empty() {
+        components
+            .schemas
+            .get_or_insert_with(Map::new)
+            .extend(definitions);
+    }
+    Ok(schema)
+}
+
+fn convert(schema: schemars::schema::Schema) -> Result<Referenceable<Schema>, serde_json::Error> {
+    let mut value = serde_json::to_value(schema)?;
+    replace_boolean_schemas(&mut value);
+    serde_json::from_value(value)
+}
+
+/// Rewrite boolean schemas, which OpenAPI 3.0 does not have, wherever a schema is expected: `true` accepts anything like `{}` and `false` nothing like `{"not": {}}`.
+///
+/// `additionalProperties` keeps its boolean, which 3.0 allows.
+fn replace_boolean_schemas(value: &mut Any) {
+    match value {
+        Any::Bool(true) => *value = json!({}),
+        Any::Bool(false) => *value = json!({"not": {}}),
+        Any::Object(object) => {
+            if let Some(Any::Object(properties)) = object.get_mut("properties") {
+                properties.values_mut().for_each(replace_boolean_schemas);
+            }
+            for keyword in ["items", "not"] {
+                if let Some(schema) = object.get_mut(keyword) {
+                    replace_boolean_schemas(schema);
+                }
+            }
+            for keyword in ["allOf", "anyOf", "oneOf"] {
+                if let Some(Any::Array(schemas)) = object.get_mut(keyword) {
+                    schemas.iter_mut().for_each(replace_boolean_schemas);
+                }
+            }
+            if let Some(additional @ Any::Object(_)) = object.get_mut("additionalProperties") {
+                replace_boolean_schemas(additional);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+    use serde_json::json;
+
+    #[allow(dead_code)]
+    #[derive(schemars::JsonSchema)]
+    enum Kind {
+        Cat,
+        Dog,
+    }
+
+    #[allow(dead_code)]
+    #[derive(schemars::JsonSchema)]
+    struct Pet {
+        name: String,
+        kind: Kind,
+        tags: Vec<String>,
+        age: Option<u32>,
+    }
+
+    #[test]
+    fn should_derive_schema_with_definitions() {
+        let mut components = Components::new();
+        assert_eq!(
+            schema_from_type::<Pet>(&mut components).unwrap(),
+            Referenceable::schema_ref("Pet")
+        );
+        let schemas = components.schemas.unwrap();
+        let pet = schemas["Pet"].as_data().unwrap();
+        assert_eq!(pet._type.as_deref(), Some("object"));
+        assert_eq!(
+            pet.required,
+            Some(vec![
+                "kind".to_string(),
+                "name".to_string(),
+                "tags".to_string()
+            ])
+        );
+        let properties = pet.properties.as_ref().unwrap();
+        assert_eq!(properties["kind"], Referenceable::schema_ref("Kind"));
+        assert_eq!(
+            properties["tags"].as_data().unwrap().items.as_deref(),
+            Some(&Referenceable::Data(Schema {
+                _type: Some("string".to_string()),
+                ..Default::default()
+            }))
+        );
+        assert_eq!(properties["age"].as_data().unwrap().nullable, Some(true));
+        assert_eq!(
+            schemas["Kind"].as_data().unwrap()._enum,
+            Some(vec![json!("Cat"), json!("Dog")])
+        );
+
+        let mut components = Components::new();
+        let list = schema_from_type::<Vec<u8>>(&mut components).unwrap();
+        assert_eq!(list.as_data().unwrap()._type.as_deref(), Some("array"));
+        assert!(components.schemas.is_none());
+    }
+
+    #[test]
+    fn should_keep_false_schemas_rejecting_everything() {
+        let never: schemars::schema::Schema = serde_json::from_value(json!(false)).unwrap();
+        let never = super::convert(never).unwrap();
+        assert_eq!(never.as_data().unwrap().to_value(), json!({"not": {}}));
+
+        let object: schemars::schema::Schema = serde_json::from_value(json!({
+            "type": "object",
+            "properties": {"never": false, "anything": true},
+            "additionalProperties": false
+        }))
+        .unwrap();
+        let object = super::convert(object).unwrap();
+        assert_eq!(
+            object.as_data().unwrap().to_value(),
+            json!({
+                "type": "object",
+                "properties": {"never": {"not": {}}, "anything": {}},
+                "additionalProperties": false
+            })
+        );
+    }
+}
@@ -0,0 +1,352 @@
+//! Parameter serialization following the `style` and `explode` rules.
+
+use crate::{Any, Parameter, ParameterIn, ParameterStyle};
+use std::fmt;
+
+/// Why a value could not be serialized for a parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SerializeError {
+    /// the parameter does not live in the part of the request being serialized
+    WrongLocation(ParameterIn),
+    /// the style is not supported for this location
+    UnsupportedStyle(ParameterStyle),
+    /// the style can not represent this value, e.g. an object with `pipeDelimited`
+    UnsupportedValue(String),
+}
+
+impl fmt::Display for SerializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SerializeError::WrongLocation(location) => {
+                write!(f, "parameter is located in {}", location.as_str())
+            }
+            SerializeError::UnsupportedStyle(style) => write!(f, "unsupported style {:?}", style),
+            SerializeError::UnsupportedValue(reason) => write!(f, "unsupported value: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for SerializeError {}
+
+impl Parameter {
+    /// The declared `explode`, or the default for the effective style: `true` for `form`, `false` otherwise.
+    pub fn effective_explode(&self) -> bool {
+        self.explode
+            .unwrap_or(self.effective_style() == ParameterStyle::Form)
+    }
+
+    /// Turn a value into query string pairs according to the parameter's `style` and `explode`.
+    ///
+    /// `form`, `spaceDelimited`, `pipeDelimited` and `deepObject` are supported, `deepObject` only for objects and whatever `explode` says. Keys and values are returned unencoded, percent-encoding is left to the caller.
+    ///
+    /// ```rust
+    /// use oas::Parameter;
+    /// use serde_json::json;
+    ///
+    /// let parameter: Parameter = serde_json::from_value(json!({"name": "id", "in": "query"})).unwrap();
+    /// let pairs = parameter.serialize_query(&json!([1, 2])).unwrap();
+    /// assert_eq!(pairs, vec![("id".to_string(), "1".to_string()), ("id".to_string(), "2".to_string())]);
+    /// ```
+    pub fn serialize_query(&self, value: &Any) -> Result<Vec<(String, String)>, SerializeError> {
+        if self._in != ParameterIn::Query {
+            return Err(SerializeError::WrongLocation(self._in));
+        }
+        let style = self.effective_style();
+        let explode = self.effective_explode();
+        let name = self.name.clone();
+        if style == ParameterStyle::DeepObject {
+            let Any::Object(object) = value else {
+                return Err(SerializeError::UnsupportedValue(
+                    "DeepObject can only serialize an object".to_owned(),
+                ));
+            };
+            return object
+                .iter()
+                .map(|(key, value)| {
+                    primitive(value).map(|value| (format!("{}[{}]", name, key), value))
+                })
+                .collect();
+        }
+        let delimiter = match &style {
+            ParameterStyle::Form => ",",
+            ParameterStyle::SpaceDelimited => " ",
+            ParameterStyle::PipeDelimited => "|",
+            _ => return Err(SerializeError::UnsupportedStyle(style)),
+        };
+        match value {
+            Any::Array(elements) => {
+                let elements = elements
+                    .iter()
+                    .map(primitive)
+                    .collect::<Result<Vec<_>, _>>()?;
+                if explode {
+                    Ok(elements.into_iter().map(|it| (name.clone(), it)).collect())
+                } else {
+                    Ok(vec![(name, elements.join(delimiter))])
+                }
+            }
+            Any::Object(object) => {
+                if style != ParameterStyle::Form {
+                    return Err(SerializeError::UnsupportedValue(format!(
+                        "{:?} can not serialize an object",
+                        style
+                    )));
+                }
+                let entries = object
+                    .iter()
+                    .map(|(key, value)| primitive(value).map(|value| (key.clone(), value)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if explode {
+                    Ok(entries)
+                } else {
+                    let flattened: Vec<String> = entries
+                        .into_iter()
+                        .flat_map(|(key, value)| [key, value])
+                        .collect();
+                    Ok(vec![(name, flattened.join(","))])
+                }
+            }
+            value => Ok(vec![(name, primitive(value)?)]),
+        }
+    }
+
+    /// Turn a value into the text replacing `{name}` in the path template, according to the parameter's `style` and `explode`.
+    ///
+    /// `simple`, `label` and `matrix` are supported. The text is returned unencoded, percent-encoding is left to the caller.
+    ///
+    /// ```rust
+    /// use oas::Parameter;
+    /// use serde_json::json;
+    ///
+    /// let parameter: Parameter =
+    ///     serde_json::from_value(json!({"name": "id", "in": "path", "required": true, "style": "matrix"})).unwrap();
+    /// assert_eq!(parameter.serialize_path(&json!(5)).unwrap(), ";id=5");
+    /// ```
+    pub fn serialize_path(&self, value: &Any) -> Result<String, SerializeError> {
+        if self._in != ParameterIn::Path {
+            return Err(SerializeError::WrongLocation(self._in));
+        }
+        let style = self.effective_style();
+        let explode = self.effective_explode();
+        // what starts the value and what separates exploded elements
+        let (prefix, separator) = match &style {
+            ParameterStyle::Simple => ("", ","),
+            ParameterStyle::Label => (".", "."),
+            ParameterStyle::Matrix => (";", ";"),
+            _ => return Err(SerializeError::UnsupportedStyle(style)),
+        };
+        let named = |value: String| match style {
+            ParameterStyle::Matrix if value.is_empty() => self.name.clone(),
+            ParameterStyle::Matrix => format!("{}={}", self.name, value),
+            _ => value,
+        };
+        let serialized = match value {
+            Any::Array(elements) => {
+                let elements = elements
+                    .iter()
+                    .map(primitive)
+                    .collect::<Result<Vec<_>, _>>()?;
+                if explode {
+                    let elements: Vec<String> = elements.into_iter().map(named).collect();
+                    elements.join(separator)
+                } else {
+                    named(elements.join(","))
+                }
+            }
+            Any::Object(object) => {
+                let entries = object
+                    .iter()
+                    .map(|(key, value)| primitive(value).map(|value| (key.clone(), value)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if explode {
+                    let entries: Vec<String> = entries
+                        .into_iter()
+                        .map(|(key, value)| format!("{}={}", key, value))
+                        .collect();
+                    entries.join(separator)
+                } else {
+                    let flattened: Vec<String> = entries
+                        .into_iter()
+                        .flat_map(|(key, value)| [key, value])
+                        .collect();
+                    named(flattened.join(","))
+                }
+            }
+            value => named(primitive(value)?),
+        };
+        Ok(format!("{}{}", prefix, serialized))
+    }
+}
+
+/// the textual form of a primitive value
+pub(crate) fn primitive(value: &Any) -> Result<String, SerializeError> {
+    match value {
+        Any::Null => Ok(String::new()),
+        Any::String(string) => Ok(string.clone()),
+        Any::Bool(_) | Any::Number(_) => Ok(value.to_string()),
+        Any::Array(_) | Any::Object(_) => Err(SerializeError::UnsupportedValue(
+            "nested arrays and objects can not be serialized".to_owned(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Parameter, SerializeError};
+    use serde_json::{json, Value};
+
+    fn query(style: Value, explode: Value) -> Parameter {
+        serde_json::from_value(
+            json!({"name": "id", "in": "query", "style": style, "explode": explode}),
+        )
+        .unwrap()
+    }
+
+    fn path(style: &str, explode: bool) -> Parameter {
+        serde_json::from_value(json!({"name": "color", "in": "path", "required": true, "style": style, "explode": explode}))
+            .unwrap()
+    }
+
+    fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn should_serialize_arrays() {
+        let array = json!([3, 4, 5]);
+        assert_eq!(
+            query(json!("form"), json!(true))
+                .serialize_query(&array)
+                .unwrap(),
+            pairs(&[("id", "3"), ("id", "4"), ("id", "5")])
+        );
+        assert_eq!(
+            query(json!("form"), json!(false))
+                .serialize_query(&array)
+                .unwrap(),
+            pairs(&[("id", "3,4,5")])
+        );
+        assert_eq!(
+            query(json!("spaceDelimited"), json!(false))
+                .serialize_query(&array)
+                .unwrap(),
+            pairs(&[("id", "3 4 5")])
+        );
+        assert_eq!(
+            query(json!("pipeDelimited"), json!(false))
+                .serialize_query(&array)
+                .unwrap(),
+            pairs(&[("id", "3|4|5")])
+        );
+    }
+
+    #[test]
+    fn should_serialize_objects() {
+        let object = json!({"role": "admin", "firstName": "Alex"});
+        assert_eq!(
+            query(json!("form"), json!(true))
+                .serialize_query(&object)
+                .unwrap(),
+            pairs(&[("firstName", "Alex"), ("role", "admin")])
+        );
+        assert_eq!(
+            query(json!("form"), json!(false))
+                .serialize_query(&object)
+                .unwrap(),
+            pairs(&[("id", "firstName,Alex,role,admin")])
+        );
+        assert!(matches!(
+            query(json!("pipeDelimited"), json!(false)).serialize_query(&object),
+            Err(SerializeError::UnsupportedValue(_))
+        ));
+    }
+
+    #[test]
+    fn should_use_form_explode_by_default() {
+        let parameter: Parameter =
+            serde_json::from_value(json!({"name": "id", "in": "query"})).unwrap();
+        assert_eq!(
+            parameter.serialize_query(&json!([1, 2])).unwrap(),
+            pairs(&[("id", "1"), ("id", "2")])
+        );
+        assert_eq!(
+            parameter.serialize_query(&json!(5)).unwrap(),
+            pairs(&[("id", "5")])
+        );
+    }
+
+    #[test]
+    fn should_serialize_path_styles() {
+        let scalar = json!("blue");
+        let array = json!(["blue", "black", "brown"]);
+        let object = json!({"G": 200, "R": 100});
+        let cases = [
+            ("simple", false, ["blue", "blue,black,brown", "G,200,R,100"]),
+            ("simple", true, ["blue", "blue,black,brown", "G=200,R=100"]),
+            (
+                "label",
+                false,
+                [".blue", ".blue,black,brown", ".G,200,R,100"],
+            ),
+            (
+                "label",
+                true,
+                [".blue", ".blue.black.brown", ".G=200.R=100"],
+            ),
+            (
+                "matrix",
+                false,
+                [
+                    ";color=blue",
+                    ";color=blue,black,brown",
+                    ";color=G,200,R,100",
+                ],
+            ),
+            (
+                "matrix",
+                true,
+                [
+                    ";color=blue",
+                    ";color=blue;color=black;color=brown",
+                    ";G=200;R=100",
+                ],
+            ),
+        ];
+        for (style, explode, [expected_scalar, expected_array, expected_object]) in cases {
+            let parameter = path(style, explode);
+            assert_eq!(parameter.serialize_path(&scalar).unwrap(), expected_scalar);
+            assert_eq!(parameter.serialize_path(&array).unwrap(), expected_array);
+            assert_eq!(parameter.serialize_path(&object).unwrap(), expected_object);
+        }
+        assert_eq!(
+            path("matrix", false).serialize_path(&json!("")).unwrap(),
+            ";color"
+        );
+        assert!(matches!(
+            path("form", false).serialize_path(&scalar),
+            Err(SerializeError::UnsupportedStyle(_))
+        ));
+        assert!(matches!(
+            query(json!("form"), json!(true)).serialize_path(&scalar),
+            Err(SerializeError::WrongLocation(_))
+        ));
+    }
+
+    #[test]
+    fn should_serialize_deep_objects() {
+        let parameter = query(json!("deepObject"), json!(true));
+        assert_eq!(
+            parameter.serialize_query(&json!({"a": 1, "b": 2})).unwrap(),
+            pairs(&[("id[a]", "1"), ("id[b]", "2")])
+        );
+        for value in [json!(1), json!([1, 2])] {
+            assert!(matches!(
+                parameter.serialize_query(&value),
+                Err(SerializeError::UnsupportedValue(_))
+            ));
+        }
+    }
+}
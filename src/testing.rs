@@ -0,0 +1,55 @@
+//! Assertions for conformance tests of documents, available with the `testing` feature.
+//!
+//! ```rust
+//! use oas::testing::assert_roundtrip;
+//!
+//! assert_roundtrip(include_str!("../examples/v3.0/json/petstore.json"));
+//! ```
+
+use assert_json_diff::assert_json_eq;
+
+use crate::{Any, OpenAPIV3};
+
+/// Assert that a JSON document parses and serializes back to the same JSON, panicking with a diff otherwise.
+#[track_caller]
+pub fn assert_roundtrip(json: &str) {
+    let original: Any = serde_json::from_str(json).expect("the document should be valid JSON");
+    let spec: OpenAPIV3 =
+        serde_json::from_str(json).expect("the document should be an OpenAPI document");
+    let serialized = serde_json::to_value(&spec).expect("the document should serialize");
+    assert_json_eq!(serialized, original);
+}
+
+/// Assert that two documents only differ in ways [`OpenAPIV3::canonicalize`] irons out, such as key order or empty values, panicking with a diff otherwise.
+#[track_caller]
+pub fn assert_semantically_equal(a: &OpenAPIV3, b: &OpenAPIV3) {
+    let a = a.canonicalize().expect("the document should serialize");
+    let b = b.canonicalize().expect("the document should serialize");
+    assert_json_eq!(a, b);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const PETSTORE: &str = include_str!("../examples/v3.0/json/petstore.json");
+
+    #[test]
+    fn should_accept_petstore() {
+        assert_roundtrip(PETSTORE);
+
+        let spec: OpenAPIV3 = serde_json::from_str(PETSTORE).unwrap();
+        let mut emptied = spec.clone();
+        emptied.tags = Some(vec![]);
+        assert_semantically_equal(&spec, &emptied);
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_reject_changed_documents() {
+        let spec: OpenAPIV3 = serde_json::from_str(PETSTORE).unwrap();
+        let mut changed = spec.clone();
+        changed.info.title = "Cat store".to_string();
+        assert_semantically_equal(&spec, &changed);
+    }
+}
@@ -0,0 +1,744 @@
+//! Document-wide rewrites of an [`OpenAPIV3`].
+
+use crate::refs::WalkRefs;
+use crate::visit::{walk_mut, VisitorMut};
+use crate::{
+    Any, Components, HttpMethod, Map, MediaType, OpenAPIV3, Operation, Referenceable, Schema,
+    SecurityRequirement, NAMED_MAPS,
+};
+use std::fmt;
+
+/// Why a component could not be renamed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenameError {
+    /// `kind` is not one of the `components` maps, e.g. `schemas`
+    UnknownKind(String),
+    /// there is no component with the old name
+    NotFound(String),
+    /// a component with the new name already exists
+    AlreadyExists(String),
+}
+
+impl fmt::Display for RenameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenameError::UnknownKind(kind) => write!(f, "unknown component kind `{}`", kind),
+            RenameError::NotFound(name) => write!(f, "component `{}` does not exist", name),
+            RenameError::AlreadyExists(name) => write!(f, "component `{}` already exists", name),
+        }
+    }
+}
+
+impl std::error::Error for RenameError {}
+
+/// Why the `allOf` members of a schema could not be merged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FlattenError {
+    /// a member is a reference that does not resolve through `components`
+    Unresolved(String),
+    /// members lead back to a schema being flattened
+    Cycle(String),
+    /// two members define the same property differently
+    PropertyConflict(String),
+    /// two members set the same keyword, e.g. `type` or `maximum`, to different values
+    KeywordConflict(String),
+}
+
+impl fmt::Display for FlattenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlattenError::Unresolved(reference) => write!(f, "can not resolve `{}`", reference),
+            FlattenError::Cycle(reference) => write!(f, "`{}` refers back to itself", reference),
+            FlattenError::PropertyConflict(name) => {
+                write!(f, "property `{}` is defined differently", name)
+            }
+            FlattenError::KeywordConflict(keyword) => {
+                write!(f, "`{}` is set to different values", keyword)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FlattenError {}
+
+impl OpenAPIV3 {
+    /// Hoist inline request and response schemas into `components.schemas`, replacing them with `$ref`s.
+    ///
+    /// Only structured schemas, the ones with `properties` or inline `items`, are hoisted. Each is named by `name_fn(path, method)`; identical schemas share one component, and a name already taken by a different schema gets a numeric suffix.
+    pub fn extract_inline_schemas(&mut self, name_fn: impl Fn(&str, HttpMethod) -> String) {
+        let existing = self
+            .components
+            .as_ref()
+            .and_then(|components| components.schemas.as_ref());
+        let mut hoisted: Vec<(String, Schema)> = vec![];
+
+        for (path, item) in self.paths.iter_mut() {
+            for (method, operation) in item.operations_mut() {
+                for media_type in media_types_mut(operation) {
+                    let schema = match &media_type.schema {
+                        Some(Referenceable::Data(schema)) if is_structured(schema) => schema,
+                        _ => continue,
+                    };
+                    let known = existing
+                        .into_iter()
+                        .flatten()
+                        .map(|(name, known)| (name, known.as_data()))
+                        .chain(hoisted.iter().map(|(name, known)| (name, Some(known))))
+                        .find(|(_, known)| *known == Some(schema))
+                        .map(|(name, _)| name.clone());
+                    let name = match known {
+                        Some(name) => name,
+                        None => {
+                            let name = unique_name(name_fn(path, method), |name| {
+                                existing.is_some_and(|schemas| schemas.contains_key(name))
+                                    || hoisted.iter().any(|(taken, _)| taken == name)
+                            });
+                            hoisted.push((name.clone(), schema.clone()));
+                            name
+                        }
+                    };
+                    media_type.schema = Some(Referenceable::schema_ref(&name));
+                }
+            }
+        }
+
+        if !hoisted.is_empty() {
+            self.components
+                .get_or_insert_with(Components::new)
+                .schemas
+                .get_or_insert_with(Map::new)
+                .extend(
+                    hoisted
+                        .into_iter()
+                        .map(|(name, schema)| (name, Referenceable::Data(schema))),
+                );
+        }
+    }
+}
+
+impl OpenAPIV3 {
+    /// Rename the component `old` of `kind` (e.g. `schemas`) to `new`, rewriting every `$ref` to it.
+    ///
+    /// Renaming a security scheme also renames it in the `security` requirements of the document and of every operation. Returns the number of references and requirements updated.
+    pub fn rename_component(
+        &mut self,
+        kind: &str,
+        old: &str,
+        new: &str,
+    ) -> Result<usize, RenameError> {
+        if !KINDS.contains(&kind) {
+            return Err(RenameError::UnknownKind(kind.to_string()));
+        }
+        let components = self
+            .components
+            .as_mut()
+            .ok_or_else(|| RenameError::NotFound(old.to_string()))?;
+        match kind {
+            "schemas" => rename_key(&mut components.schemas, old, new)?,
+            "responses" => rename_key(&mut components.responses, old, new)?,
+            "parameters" => rename_key(&mut components.parameters, old, new)?,
+            "examples" => rename_key(&mut components.examples, old, new)?,
+            "requestBodies" => rename_key(&mut components.request_bodies, old, new)?,
+            "headers" => rename_key(&mut components.headers, old, new)?,
+            "securitySchemes" => rename_key(&mut components.security_schemes, old, new)?,
+            "links" => rename_key(&mut components.links, old, new)?,
+            "callbacks" => rename_key(&mut components.callbacks, old, new)?,
+            _ => return Err(RenameError::UnknownKind(kind.to_string())),
+        }
+
+        let old_ref = format!("#/components/{}/{}", kind, escape(old));
+        let new_ref = format!("#/components/{}/{}", kind, escape(new));
+        let mut updated = 0;
+        self.walk_refs(&mut |_, reference| {
+            let rest = match reference.strip_prefix(&old_ref) {
+                Some(rest) if rest.is_empty() || rest.starts_with('/') => rest,
+                _ => return,
+            };
+            *reference = format!("{}{}", new_ref, rest);
+            updated += 1;
+        });
+        if kind == "securitySchemes" {
+            updated += rename_requirements(&mut self.security, old, new);
+            let mut renamer = RequirementRenamer { old, new, updated };
+            walk_mut(self, &mut renamer);
+            updated = renamer.updated;
+        }
+        Ok(updated)
+    }
+}
+
+/// The kinds of components, as named in the `components` object.
+pub(crate) const KINDS: [&str; 9] = [
+    "schemas",
+    "responses",
+    "parameters",
+    "examples",
+    "requestBodies",
+    "headers",
+    "securitySchemes",
+    "links",
+    "callbacks",
+];
+
+/// Renames a security scheme in the requirements of every operation.
+struct RequirementRenamer<'a> {
+    old: &'a str,
+    new: &'a str,
+    updated: usize,
+}
+
+impl VisitorMut for RequirementRenamer<'_> {
+    fn visit_operation(&mut self, _path: &str, _method: HttpMethod, operation: &mut Operation) {
+        self.updated += rename_requirements(&mut operation.security, self.old, self.new);
+    }
+}
+
+/// Rename a scheme in each requirement naming it, returning how many were.
+fn rename_requirements(
+    requirements: &mut Option<Vec<SecurityRequirement>>,
+    old: &str,
+    new: &str,
+) -> usize {
+    let mut renamed = 0;
+    for requirement in requirements.iter_mut().flatten() {
+        let mut data = Some(std::mem::take(&mut requirement.data));
+        if rename_key(&mut data, old, new).is_ok() {
+            renamed += 1;
+        }
+        requirement.data = data.unwrap_or_default();
+    }
+    renamed
+}
+
+impl OpenAPIV3 {
+    /// A canonical JSON form for storing and comparing documents: object keys sorted, `null` values and empty arrays and objects dropped.
+    ///
+    /// Empty values that carry meaning are kept: `security` requirements (`[]` and `{}` opt out of security), OAuth `scopes`, `paths`, entries of maps keyed by names such as an any-type `{}` property, and user data under `example`, `default`, `enum` and `value`. Canonicalizing a canonical value changes nothing.
+    ///
+    /// Fails if the document can not be serialized, e.g. because of non-string keys in extensions.
+    pub fn canonicalize(&self) -> Result<Any, serde_json::Error> {
+        Ok(canonical(serde_json::to_value(self)?)
+            .unwrap_or_else(|| Any::Object(Default::default())))
+    }
+}
+
+impl OpenAPIV3 {
+    /// A hex digest of the [canonical form](OpenAPIV3::canonicalize), equal for documents that only differ in key order, formatting or empty fields, e.g. for an `ETag`.
+    ///
+    /// This is a 64-bit FNV-1a hash, good for change detection but not collision resistant.
+    pub fn content_hash(&self) -> Result<String, serde_json::Error> {
+        const OFFSET: u64 = 0xcbf29ce484222325;
+        const PRIME: u64 = 0x100000001b3;
+        let hash = self
+            .canonicalize()?
+            .to_string()
+            .bytes()
+            .fold(OFFSET, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+            });
+        Ok(format!("{:016x}", hash))
+    }
+}
+
+/// Keywords whose value is kept as is apart from key order. Entries of maps keyed by names are never taken for them, so a `default` response is canonicalized like any other.
+const VERBATIM_KEYS: [&str; 6] = ["security", "scopes", "example", "default", "enum", "value"];
+
+fn canonical(value: Any) -> Option<Any> {
+    match value {
+        Any::Null => None,
+        Any::Array(values) => {
+            let values: Vec<Any> = values.into_iter().filter_map(canonical).collect();
+            (!values.is_empty()).then_some(Any::Array(values))
+        }
+        Any::Object(object) => {
+            let mut entries: Vec<(String, Any)> = object.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            let object: serde_json::Map<String, Any> = entries
+                .into_iter()
+                .filter_map(|(key, value)| {
+                    let value = if key == "paths" {
+                        canonical_map(value).or_else(|| Some(Any::Object(Default::default())))
+                    } else if NAMED_MAPS.contains(&key.as_str()) || key == "examples" {
+                        canonical_map(value)
+                    } else if VERBATIM_KEYS.contains(&key.as_str()) {
+                        Some(sorted(value))
+                    } else {
+                        canonical(value)
+                    };
+                    value.map(|value| (key, value))
+                })
+                .collect();
+            (!object.is_empty()).then_some(Any::Object(object))
+        }
+        value => Some(value),
+    }
+}
+
+/// Canonicalize every entry of a map keyed by names, whatever the names are. An entry left empty stays as `{}`, since its name alone carries meaning.
+fn canonical_map(value: Any) -> Option<Any> {
+    let Any::Object(map) = value else {
+        return canonical(value);
+    };
+    let mut entries: Vec<(String, Any)> = map.into_iter().collect();
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+    let map: serde_json::Map<String, Any> = entries
+        .into_iter()
+        .map(|(name, value)| {
+            let value = canonical(value).unwrap_or_else(|| Any::Object(Default::default()));
+            (name, value)
+        })
+        .collect();
+    (!map.is_empty()).then_some(Any::Object(map))
+}
+
+/// Sort object keys recursively without dropping anything.
+fn sorted(value: Any) -> Any {
+    match value {
+        Any::Array(values) => Any::Array(values.into_iter().map(sorted).collect()),
+        Any::Object(object) => {
+            let mut entries: Vec<(String, Any)> = object.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Any::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, sorted(value)))
+                    .collect(),
+            )
+        }
+        value => value,
+    }
+}
+
+/// Move `old` to `new`, keeping its position in the map.
+fn rename_key<T>(
+    map: &mut Option<Map<String, T>>,
+    old: &str,
+    new: &str,
+) -> Result<(), RenameError> {
+    let map = map
+        .as_mut()
+        .ok_or_else(|| RenameError::NotFound(old.to_string()))?;
+    if map.contains_key(new) {
+        return Err(RenameError::AlreadyExists(new.to_string()));
+    }
+    #[cfg(not(feature = "indexmap"))]
+    {
+        let value = map
+            .remove(old)
+            .ok_or_else(|| RenameError::NotFound(old.to_string()))?;
+        map.insert(new.to_string(), value);
+    }
+    #[cfg(feature = "indexmap")]
+    {
+        let (index, _, value) = map
+            .shift_remove_full(old)
+            .ok_or_else(|| RenameError::NotFound(old.to_string()))?;
+        map.shift_insert(index, new.to_string(), value);
+    }
+    Ok(())
+}
+
+/// Escape a key for use as a JSON pointer segment.
+pub(crate) fn escape(name: &str) -> String {
+    name.replace('~', "~0").replace('/', "~1")
+}
+
+fn is_structured(schema: &Schema) -> bool {
+    schema.properties.is_some() || schema.items.as_deref().is_some_and(Referenceable::is_data)
+}
+
+pub(crate) fn unique_name(base: String, is_taken: impl Fn(&str) -> bool) -> String {
+    if !is_taken(&base) {
+        return base;
+    }
+    (2..)
+        .map(|suffix| format!("{}{}", base, suffix))
+        .find(|name| !is_taken(name))
+        .expect("an unused suffix always exists")
+}
+
+/// The inline media types of an operation's request body and responses.
+fn media_types_mut(operation: &mut Operation) -> Vec<&mut MediaType> {
+    let request = match &mut operation.request_body {
+        Some(Referenceable::Data(body)) => Some(&mut body.content),
+        _ => None,
+    };
+    let responses = operation
+        .responses
+        .default
+        .iter_mut()
+        .chain(operation.responses.data.values_mut())
+        .filter_map(|response| match response {
+            Referenceable::Data(response) => response.content.as_mut(),
+            Referenceable::Reference(_) => None,
+        });
+    request
+        .into_iter()
+        .chain(responses)
+        .flat_map(|content| content.values_mut())
+        .collect()
+}
+
+impl Schema {
+    /// Merge the `allOf` members of this schema into a single schema, resolving references through `components`.
+    ///
+    /// Properties of all members are gathered and `required` lists are joined. Any other keyword, such as `type` or `maximum`, may be set by several members only to the same value; `description`, `title`, `example`, `default` and `x-` extensions are documentation, so the first one wins. Nested `allOf`s are flattened too, and a merged schema with properties but no `type` becomes `type: object`.
+    pub fn flatten_all_of(&self, components: &Components) -> Result<Schema, FlattenError> {
+        flatten(self, components, &mut vec![])
+    }
+}
+
+fn flatten(
+    schema: &Schema,
+    components: &Components,
+    stack: &mut Vec<String>,
+) -> Result<Schema, FlattenError> {
+    let Any::Object(mut merged) = schema.to_value() else {
+        unreachable!("a schema serializes to an object")
+    };
+    merged.remove("allOf");
+    for member in schema.subschemas("allOf") {
+        let reference = member
+            .as_reference()
+            .map(|reference| reference._ref.clone());
+        let resolved = member
+            .resolve(components)
+            .ok_or_else(|| FlattenError::Unresolved(reference.clone().unwrap_or_default()))?;
+        if let Some(reference) = &reference {
+            if stack.contains(reference) {
+                return Err(FlattenError::Cycle(reference.clone()));
+            }
+            stack.push(reference.clone());
+        }
+        let flattened = flatten(resolved, components, stack)?;
+        if reference.is_some() {
+            stack.pop();
+        }
+        let Any::Object(flattened) = flattened.to_value() else {
+            unreachable!("a schema serializes to an object")
+        };
+        merge_keywords(&mut merged, flattened)?;
+    }
+    if merged.contains_key("properties") && !merged.contains_key("type") {
+        merged.insert("type".to_string(), Any::from("object"));
+    }
+    Ok(serde_json::from_value(Any::Object(merged)).expect("merged schemas are schemas"))
+}
+
+fn merge_keywords(
+    into: &mut serde_json::Map<String, Any>,
+    from: serde_json::Map<String, Any>,
+) -> Result<(), FlattenError> {
+    for (keyword, value) in from {
+        let Some(existing) = into.get_mut(&keyword) else {
+            into.insert(keyword, value);
+            continue;
+        };
+        match (keyword.as_str(), existing, value) {
+            ("properties", Any::Object(properties), Any::Object(added)) => {
+                for (name, property) in added {
+                    match properties.get(&name) {
+                        Some(defined) if *defined != property => {
+                            return Err(FlattenError::PropertyConflict(name))
+                        }
+                        Some(_) => {}
+                        None => {
+                            properties.insert(name, property);
+                        }
+                    }
+                }
+            }
+            ("required", Any::Array(required), Any::Array(added)) => {
+                for name in added {
+                    if !required.contains(&name) {
+                        required.push(name);
+                    }
+                }
+            }
+            ("description" | "title" | "example" | "default", _, _) => {}
+            (keyword, _, _) if keyword.starts_with("x-") => {}
+            (_, existing, value) if *existing == value => {}
+            _ => return Err(FlattenError::KeywordConflict(keyword)),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::builders::{operation, request_body, response};
+    use crate::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_rename_component_and_its_references() {
+        let mut spec: OpenAPIV3 =
+            serde_json::from_str(include_str!("../examples/v3.0/json/petstore.json")).unwrap();
+
+        assert_eq!(
+            spec.rename_component("schemas", "Pet", "Pet"),
+            Err(RenameError::AlreadyExists("Pet".to_string()))
+        );
+        assert_eq!(spec.rename_component("schemas", "Pets", "PetList"), Ok(2));
+        assert_eq!(spec.rename_component("schemas", "Pet", "PetV2"), Ok(1));
+
+        let schemas = spec.components.as_ref().unwrap().schemas.as_ref().unwrap();
+        assert!(schemas.contains_key("PetV2") && !schemas.contains_key("Pet"));
+        let json = spec.to_string();
+        assert!(!json.contains("#/components/schemas/Pets\""));
+        assert_eq!(json.matches("#/components/schemas/PetList").count(), 2);
+        assert_eq!(
+            schemas["PetList"].as_data().unwrap().items.as_deref(),
+            Some(&Referenceable::schema_ref("PetV2"))
+        );
+        assert_eq!(
+            spec.rename_component("widgets", "Pet", "Pet2"),
+            Err(RenameError::UnknownKind("widgets".to_string()))
+        );
+        assert_eq!(
+            spec.rename_component("widgets", "Missing", "Pet2"),
+            Err(RenameError::UnknownKind("widgets".to_string()))
+        );
+    }
+
+    #[test]
+    fn should_rename_security_requirements_with_their_scheme() {
+        let mut spec: OpenAPIV3 = serde_json::from_value(json!({
+            "openapi": "3.0.3",
+            "info": {"title": "secured", "version": "1.0"},
+            "security": [{"api_key": []}, {"oauth": ["read"]}],
+            "paths": {"/pets": {"get": {
+                "security": [{"api_key": [], "oauth": ["write"]}],
+                "responses": {"200": {"description": "ok"}}
+            }}},
+            "components": {"securitySchemes": {
+                "api_key": {"type": "apiKey", "name": "X-Key", "in": "header"},
+                "oauth": {"type": "http", "scheme": "bearer"}
+            }}
+        }))
+        .unwrap();
+
+        assert_eq!(
+            spec.rename_component("securitySchemes", "api_key", "ApiKey"),
+            Ok(2)
+        );
+        let json = spec.to_value();
+        assert_eq!(
+            json["security"],
+            json!([{"ApiKey": []}, {"oauth": ["read"]}])
+        );
+        assert_eq!(
+            json["paths"]["/pets"]["get"]["security"],
+            json!([{"ApiKey": [], "oauth": ["write"]}])
+        );
+    }
+
+    #[test]
+    fn should_canonicalize_idempotently() {
+        let mut spec: OpenAPIV3 =
+            serde_json::from_str(include_str!("../examples/v3.0/json/petstore-expanded.json"))
+                .unwrap();
+        spec.tags = Some(vec![]);
+        spec.get_path_mut("/pets")
+            .unwrap()
+            .get
+            .as_mut()
+            .unwrap()
+            .security = Some(vec![]);
+
+        let canonical = spec.canonicalize().unwrap();
+        assert_eq!(
+            OpenAPIV3::canonicalize(&serde_json::from_value(canonical.clone()).unwrap()).unwrap(),
+            canonical
+        );
+        assert!(canonical.get("tags").is_none());
+        assert_eq!(canonical["paths"]["/pets"]["get"]["security"], json!([]));
+
+        let keys: Vec<&String> = canonical.as_object().unwrap().keys().collect();
+        assert_eq!(
+            keys,
+            vec!["components", "info", "openapi", "paths", "servers"]
+        );
+        assert_eq!(
+            spec.canonicalize().unwrap().to_string(),
+            canonical.to_string()
+        );
+    }
+
+    #[test]
+    fn should_canonicalize_default_responses() {
+        let spec: OpenAPIV3 =
+            serde_json::from_str(include_str!("../examples/v3.0/json/petstore-expanded.json"))
+                .unwrap();
+        let mut emptied = spec.clone();
+        let operation = emptied.get_path_mut("/pets").unwrap().get.as_mut().unwrap();
+        let Some(Referenceable::Data(response)) = operation.responses.default.as_mut() else {
+            panic!("inline default response expected");
+        };
+        response.headers = Some(Map::new());
+
+        assert_ne!(spec.to_value(), emptied.to_value());
+        assert_eq!(
+            spec.canonicalize().unwrap(),
+            emptied.canonicalize().unwrap()
+        );
+        assert_eq!(
+            spec.content_hash().unwrap(),
+            emptied.content_hash().unwrap()
+        );
+
+        let schema: Schema = serde_json::from_value(json!({
+            "type": "object",
+            "default": {"tags": []},
+            "properties": {"anything": {}}
+        }))
+        .unwrap();
+        emptied
+            .components
+            .as_mut()
+            .unwrap()
+            .schemas
+            .as_mut()
+            .unwrap()
+            .insert("Defaulted".to_string(), Referenceable::Data(schema));
+        assert_eq!(
+            emptied.canonicalize().unwrap()["components"]["schemas"]["Defaulted"]["default"],
+            json!({"tags": []})
+        );
+        assert_eq!(
+            emptied.canonicalize().unwrap()["components"]["schemas"]["Defaulted"]["properties"],
+            json!({"anything": {}})
+        );
+    }
+
+    #[test]
+    fn should_hash_equal_specs_identically() {
+        let parsed: OpenAPIV3 =
+            serde_json::from_str(include_str!("../examples/v3.0/json/petstore.json")).unwrap();
+        let mut built = OpenAPIV3::new(
+            Info::new("Swagger Petstore", "1.0.0").with_license(License::new("MIT")),
+        )
+        .add_server(Server::new("http://petstore.swagger.io/v1"));
+        built.components = parsed.components.clone();
+        built.tags = Some(vec![]);
+        for (path, item) in parsed.paths.iter().rev() {
+            built = built.add_path(path.clone(), item.clone());
+        }
+
+        assert_eq!(
+            built.content_hash().unwrap(),
+            parsed.content_hash().unwrap()
+        );
+        assert_eq!(parsed.content_hash().unwrap().len(), 16);
+        built.info.version = "1.0.1".to_string();
+        assert_ne!(
+            built.content_hash().unwrap(),
+            parsed.content_hash().unwrap()
+        );
+    }
+
+    #[test]
+    fn should_hoist_identical_schemas_once() {
+        let user: Schema = serde_json::from_value(json!({
+            "type": "object",
+            "properties": {"name": {"type": "string"}}
+        }))
+        .unwrap();
+        let body = Referenceable::Data(
+            request_body()
+                .json(Referenceable::Data(user.clone()))
+                .build(),
+        );
+        let item = PathItem {
+            post: Some(
+                operation()
+                    .request_body(body.clone())
+                    .response("201", Referenceable::Data(response("created").build()))
+                    .build(),
+            ),
+            put: Some(
+                operation()
+                    .request_body(body)
+                    .response("200", Referenceable::Data(response("updated").build()))
+                    .build(),
+            ),
+            ..PathItem::default()
+        };
+        let mut spec = OpenAPIV3::default().add_path("/users", item);
+
+        spec.extract_inline_schemas(|_, method| format!("User{}", method.as_str()));
+
+        let schemas = spec.components.unwrap().schemas.unwrap();
+        assert_eq!(schemas.len(), 1);
+        assert_eq!(schemas.get("Userput"), Some(&Referenceable::Data(user)));
+        let item = &spec.paths["/users"];
+        for method in [HttpMethod::Post, HttpMethod::Put] {
+            let body = item
+                .operation(method)
+                .unwrap()
+                .request_body
+                .as_ref()
+                .unwrap();
+            assert_eq!(
+                body.as_data().unwrap().content["application/json"].schema,
+                Some(Referenceable::schema_ref("Userput"))
+            );
+        }
+    }
+
+    #[test]
+    fn should_flatten_all_of() {
+        let components: Components = serde_json::from_value(json!({
+            "schemas": {
+                "Pet": {
+                    "type": "object",
+                    "description": "a pet",
+                    "required": ["name"],
+                    "properties": {"name": {"type": "string"}, "id": {"type": "integer"}}
+                }
+            }
+        }))
+        .unwrap();
+        let dog: Schema = serde_json::from_value(json!({
+            "description": "a dog",
+            "allOf": [
+                {"$ref": "#/components/schemas/Pet"},
+                {"required": ["name", "breed"], "properties": {"breed": {"type": "string"}}}
+            ]
+        }))
+        .unwrap();
+        assert_eq!(
+            dog.flatten_all_of(&components).unwrap().to_value(),
+            json!({
+                "type": "object",
+                "description": "a dog",
+                "required": ["name", "breed"],
+                "properties": {
+                    "name": {"type": "string"},
+                    "id": {"type": "integer"},
+                    "breed": {"type": "string"}
+                }
+            })
+        );
+
+        let conflicting: Schema = serde_json::from_value(json!({
+            "allOf": [
+                {"$ref": "#/components/schemas/Pet"},
+                {"properties": {"name": {"type": "integer"}}}
+            ]
+        }))
+        .unwrap();
+        assert_eq!(
+            conflicting.flatten_all_of(&components),
+            Err(FlattenError::PropertyConflict("name".to_string()))
+        );
+        let dangling: Schema = serde_json::from_value(json!({
+            "allOf": [{"$ref": "#/components/schemas/Cat"}]
+        }))
+        .unwrap();
+        assert!(matches!(
+            dangling.flatten_all_of(&components),
+            Err(FlattenError::Unresolved(_))
+        ));
+    }
+}
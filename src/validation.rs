@@ -0,0 +1,341 @@
+//! Best-effort validation of JSON values against a [`Schema`], and lightweight format checks of the document itself.
+
+use crate::visit::{walk, Visitor};
+use crate::{Any, Contact, ExternalDocumentation, OpenAPIV3, Referenceable, Schema, Server};
+use std::fmt;
+
+/// A single place where a value does not conform to its schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaViolation {
+    /// JSON pointer to the offending value, empty for the root.
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+impl Schema {
+    /// Check a value against `type`, `enum`, `required`, the string length and numeric range constraints, recursing into inline `properties` and `items`.
+    ///
+    /// This is best-effort: references are not followed and unknown keywords are ignored.
+    pub fn validate_value(&self, value: &Any) -> Result<(), Vec<SchemaViolation>> {
+        let mut violations = vec![];
+        self.collect_violations(value, String::new(), &mut violations);
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    fn collect_violations(&self, value: &Any, path: String, violations: &mut Vec<SchemaViolation>) {
+        let mut violate = |message: String| {
+            violations.push(SchemaViolation {
+                path: path.clone(),
+                message,
+            })
+        };
+        if value.is_null() && self.nullable == Some(true) {
+            return;
+        }
+        if let Some(expected) = self._type.as_deref() {
+            if !type_matches(expected, value) {
+                violate(format!("expected {}, found {}", expected, type_name(value)));
+                return;
+            }
+        }
+        if let Some(values) = &self._enum {
+            if !values.contains(value) {
+                violate(format!("{} is not one of the allowed values", value));
+            }
+        }
+        match value {
+            Any::String(string) => {
+                let length = string.chars().count() as u64;
+                if let Some(min) = self.min_length.filter(|min| length < *min) {
+                    violate(format!("length {} is shorter than {}", length, min));
+                }
+                if let Some(max) = self.max_length.filter(|max| length > *max) {
+                    violate(format!("length {} is longer than {}", length, max));
+                }
+            }
+            Any::Number(number) => {
+                let number = number.as_f64().unwrap_or_default();
+                if let Some(minimum) = self.minimum.as_ref().and_then(|it| it.as_f64()) {
+                    let exclusive = self.exclusive_minimum == Some(true);
+                    if number < minimum || (exclusive && number == minimum) {
+                        violate(format!("{} is less than the minimum {}", number, minimum));
+                    }
+                }
+                if let Some(maximum) = self.maximum.as_ref().and_then(|it| it.as_f64()) {
+                    let exclusive = self.exclusive_maximum == Some(true);
+                    if number > maximum || (exclusive && number == maximum) {
+                        violate(format!(
+                            "{} is greater than the maximum {}",
+                            number, maximum
+                        ));
+                    }
+                }
+            }
+            Any::Object(object) => {
+                for name in self.required.iter().flatten() {
+                    if !object.contains_key(name) {
+                        violate(format!("missing required property `{}`", name));
+                    }
+                }
+                for (name, property) in self.properties.iter().flatten() {
+                    if let (Some(value), Referenceable::Data(property)) =
+                        (object.get(name), property)
+                    {
+                        property.collect_violations(
+                            value,
+                            format!("{}/{}", path, escape_pointer(name)),
+                            violations,
+                        );
+                    }
+                }
+            }
+            Any::Array(elements) => {
+                if let Some(Referenceable::Data(items)) = self.items.as_deref() {
+                    for (index, element) in elements.iter().enumerate() {
+                        items.collect_violations(
+                            element,
+                            format!("{}/{}", path, index),
+                            violations,
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn type_matches(expected: &str, value: &Any) -> bool {
+    match expected {
+        "string" => value.is_string(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|it| it.fract() == 0.0)
+        }
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Any) -> &'static str {
+    match value {
+        Any::Null => "null",
+        Any::Bool(_) => "boolean",
+        Any::Number(_) => "number",
+        Any::String(_) => "string",
+        Any::Array(_) => "array",
+        Any::Object(_) => "object",
+    }
+}
+
+fn escape_pointer(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+impl Contact {
+    /// Warnings for a `url` or `email` that does not look like one. Only the shape is checked, nothing is looked up.
+    pub fn validate(&self) -> Vec<String> {
+        let mut warnings = vec![];
+        if let Some(url) = self.url.as_deref().filter(|url| !is_url(url)) {
+            warnings.push(format!("contact url `{}` is not a valid URL", url));
+        }
+        if let Some(email) = self.email.as_deref().filter(|email| !is_email(email)) {
+            warnings.push(format!(
+                "contact email `{}` is not a valid email address",
+                email
+            ));
+        }
+        warnings
+    }
+}
+
+impl OpenAPIV3 {
+    /// Warnings for every URL and email of the document that does not look like one: `info.termsOfService`, the contact, the license url, the `externalDocs` and the server urls at every level.
+    ///
+    /// Server urls may be relative and contain `{variables}`, every other URL must be absolute. Only the shape is checked, nothing is looked up.
+    pub fn validate_urls(&self) -> Vec<String> {
+        #[derive(Default)]
+        struct Urls(Vec<String>);
+
+        impl Visitor for Urls {
+            fn visit_server(&mut self, server: &Server) {
+                let expanded = server
+                    .url
+                    .split(['{', '}'])
+                    .enumerate()
+                    .map(|(index, part)| if index % 2 == 1 { "x" } else { part })
+                    .collect::<String>();
+                let relative = expanded.starts_with('/') || expanded.starts_with('.');
+                if !(is_url(&expanded) || relative && !expanded.contains(char::is_whitespace)) {
+                    self.0
+                        .push(format!("server url `{}` is not a valid URL", server.url));
+                }
+            }
+            fn visit_external_documentation(&mut self, docs: &ExternalDocumentation) {
+                if !is_url(&docs.url) {
+                    self.0.push(format!(
+                        "externalDocs url `{}` is not a valid URL",
+                        docs.url
+                    ));
+                }
+            }
+        }
+
+        let mut urls = Urls::default();
+        let info = &self.info;
+        if let Some(terms) = info.terms_of_service.as_deref().filter(|url| !is_url(url)) {
+            urls.0
+                .push(format!("termsOfService `{}` is not a valid URL", terms));
+        }
+        if let Some(contact) = &info.contact {
+            urls.0.extend(contact.validate());
+        }
+        let license = info
+            .license
+            .as_ref()
+            .and_then(|license| license.url.as_deref());
+        if let Some(url) = license.filter(|url| !is_url(url)) {
+            urls.0
+                .push(format!("license url `{}` is not a valid URL", url));
+        }
+        walk(self, &mut urls);
+        urls.0
+    }
+}
+
+/// an absolute URL: a scheme, `:` and something after it, without whitespace
+fn is_url(url: &str) -> bool {
+    let Some((scheme, rest)) = url.split_once(':') else {
+        return false;
+    };
+    scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        && !rest.is_empty()
+        && rest != "//"
+        && !url.contains(char::is_whitespace)
+}
+
+/// `local@domain.tld`, without whitespace
+fn is_email(email: &str) -> bool {
+    let Some((local, domain)) = email.split_once('@') else {
+        return false;
+    };
+    let labels: Vec<&str> = domain.split('.').collect();
+    !local.is_empty()
+        && !domain.contains('@')
+        && labels.len() >= 2
+        && labels.iter().all(|label| !label.is_empty())
+        && !email.contains(char::is_whitespace)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Contact, OpenAPIV3, Schema, SchemaViolation};
+    use serde_json::json;
+
+    fn user_schema() -> Schema {
+        serde_json::from_value(json!({
+            "type": "object",
+            "required": ["id", "name"],
+            "properties": {
+                "id": {"type": "integer", "minimum": 1},
+                "name": {"type": "string", "minLength": 1, "maxLength": 32},
+                "role": {"type": "string", "enum": ["admin", "member"]},
+                "tags": {"type": "array", "items": {"type": "string"}}
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn should_accept_conforming_object() {
+        let value = json!({"id": 7, "name": "kilerd", "role": "admin", "tags": ["a", "b"]});
+        assert_eq!(user_schema().validate_value(&value), Ok(()));
+    }
+
+    #[test]
+    fn should_report_missing_required_and_nested_violations() {
+        let value = json!({"id": 0, "role": "owner", "tags": ["a", 1]});
+        let violations = user_schema().validate_value(&value).unwrap_err();
+        assert_eq!(
+            violations,
+            vec![
+                SchemaViolation {
+                    path: "".to_string(),
+                    message: "missing required property `name`".to_string()
+                },
+                SchemaViolation {
+                    path: "/id".to_string(),
+                    message: "0 is less than the minimum 1".to_string()
+                },
+                SchemaViolation {
+                    path: "/role".to_string(),
+                    message: "\"owner\" is not one of the allowed values".to_string()
+                },
+                SchemaViolation {
+                    path: "/tags/1".to_string(),
+                    message: "expected string, found number".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_warn_about_malformed_urls_and_emails() {
+        let contact = Contact {
+            name: None,
+            url: Some("https://example.com/support".to_string()),
+            email: Some("support at example.com".to_string()),
+        };
+        assert_eq!(
+            contact.validate(),
+            vec!["contact email `support at example.com` is not a valid email address"]
+        );
+
+        let spec: OpenAPIV3 = serde_json::from_value(json!({
+            "openapi": "3.0.0",
+            "info": {
+                "title": "api",
+                "version": "1.0.0",
+                "termsOfService": "example.com/terms",
+                "contact": {"email": "support@example.com"},
+                "license": {"name": "MIT", "url": "https://opensource.org/licenses/MIT"}
+            },
+            "servers": [
+                {"url": "{scheme}://api.example.com/v1", "variables": {"scheme": {"default": "https"}}},
+                {"url": "/v1"},
+                {"url": "http ://bad"}
+            ],
+            "paths": {},
+            "externalDocs": {"url": "https://example.com/docs"},
+            "tags": [{"name": "pets", "externalDocs": {"url": "docs"}}]
+        }))
+        .unwrap();
+        assert_eq!(
+            spec.validate_urls(),
+            vec![
+                "termsOfService `example.com/terms` is not a valid URL",
+                "server url `http ://bad` is not a valid URL",
+                "externalDocs url `docs` is not a valid URL",
+            ]
+        );
+    }
+}
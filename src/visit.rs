@@ -0,0 +1,393 @@
+//! Walking every object of a document with a visitor.
+//!
+//! Implement the `visit_*` methods you care about and hand the visitor to [`walk`], or to [`walk_mut`] to change the document in place. Each object is visited before its children, so a visitor may change what gets visited next, e.g. by clearing a field.
+//!
+//! ```rust
+//! use oas::visit::{walk, Visitor};
+//! use oas::{OpenAPIV3, Schema};
+//!
+//! #[derive(Default)]
+//! struct CountSchemas(usize);
+//!
+//! impl Visitor for CountSchemas {
+//!     fn visit_schema(&mut self, _schema: &Schema) {
+//!         self.0 += 1;
+//!     }
+//! }
+//!
+//! let spec: OpenAPIV3 = serde_json::from_str(include_str!("../examples/v3.0/json/petstore.json")).unwrap();
+//! let mut counter = CountSchemas::default();
+//! walk(&spec, &mut counter);
+//! assert_eq!(counter.0, 11);
+//! ```
+
+use crate::{
+    Any, Callback, Component, Components, Example, ExternalDocumentation, Header, HttpMethod, Info,
+    Link, Map, MediaType, OpenAPIV3, Operation, Parameter, PathItem, Reference, Referenceable,
+    RequestBody, Response, Schema, SecurityScheme, Server, Tag,
+};
+
+/// The keywords kept in [`Schema::extras`] whose value is a schema or an array of schemas.
+const SUBSCHEMA_KEYWORDS: [&str; 5] = ["allOf", "oneOf", "anyOf", "not", "additionalProperties"];
+
+/// Generates a visitor trait and its driver, once over shared and once over mutable references.
+macro_rules! visitor {
+    ($(#[$doc:meta])* $visitor:ident, $walk:ident, $($mutability:ident)?) => {
+        $(#[$doc])*
+        #[allow(unused_variables)]
+        pub trait $visitor {
+            fn visit_info(&mut self, info: &$($mutability)? Info) {}
+            fn visit_server(&mut self, server: &$($mutability)? Server) {}
+            /// `path` is the template of the path, or the expression for path items of callbacks
+            fn visit_path_item(&mut self, path: &str, item: &$($mutability)? PathItem) {}
+            fn visit_operation(&mut self, path: &str, method: HttpMethod, operation: &$($mutability)? Operation) {}
+            fn visit_parameter(&mut self, parameter: &$($mutability)? Parameter) {}
+            fn visit_request_body(&mut self, request_body: &$($mutability)? RequestBody) {}
+            fn visit_media_type(&mut self, content_type: &str, media_type: &$($mutability)? MediaType) {}
+            fn visit_response(&mut self, response: &$($mutability)? Response) {}
+            fn visit_header(&mut self, header: &$($mutability)? Header) {}
+            fn visit_link(&mut self, link: &$($mutability)? Link) {}
+            fn visit_example(&mut self, example: &$($mutability)? Example) {}
+            fn visit_schema(&mut self, schema: &$($mutability)? Schema) {}
+            fn visit_security_scheme(&mut self, scheme: &$($mutability)? SecurityScheme) {}
+            fn visit_tag(&mut self, tag: &$($mutability)? Tag) {}
+            fn visit_external_documentation(&mut self, docs: &$($mutability)? ExternalDocumentation) {}
+            /// `kind` is the kind of component the reference should point to, e.g. `schemas`
+            fn visit_reference(&mut self, kind: &str, reference: &$($mutability)? Reference) {}
+        }
+
+        /// Visit every object of the document, depth first.
+        pub fn $walk(spec: &$($mutability)? OpenAPIV3, visitor: &mut impl $visitor) {
+            /// How to walk the inside of an object once it has been visited.
+            trait Walk {
+                fn walk(&$($mutability)? self, visitor: &mut impl $visitor);
+            }
+
+            impl<T: Walk + Component> Walk for Referenceable<T> {
+                fn walk(&$($mutability)? self, visitor: &mut impl $visitor) {
+                    match self {
+                        Referenceable::Reference(reference) => visitor.visit_reference(T::KIND, reference),
+                        Referenceable::Data(data) => data.walk(visitor),
+                    }
+                }
+            }
+
+            impl<T: Walk> Walk for Option<T> {
+                fn walk(&$($mutability)? self, visitor: &mut impl $visitor) {
+                    if let Some(value) = self {
+                        value.walk(visitor);
+                    }
+                }
+            }
+
+            impl<T: Walk> Walk for Vec<T> {
+                fn walk(&$($mutability)? self, visitor: &mut impl $visitor) {
+                    for value in self {
+                        value.walk(visitor);
+                    }
+                }
+            }
+
+            impl<T: Walk> Walk for Box<T> {
+                fn walk(&$($mutability)? self, visitor: &mut impl $visitor) {
+                    (&$($mutability)? **self).walk(visitor);
+                }
+            }
+
+            impl<T: Walk> Walk for Map<String, T> {
+                fn walk(&$($mutability)? self, visitor: &mut impl $visitor) {
+                    for (_, value) in self {
+                        value.walk(visitor);
+                    }
+                }
+            }
+
+            /// Media types need their content type, so content maps are walked by hand.
+            fn walk_content(content: &$($mutability)? Option<Map<String, MediaType>>, visitor: &mut impl $visitor) {
+                if let Some(content) = content {
+                    for (content_type, media_type) in content {
+                        walk_media_type(content_type, media_type, visitor);
+                    }
+                }
+            }
+
+            fn walk_media_type(content_type: &str, media_type: &$($mutability)? MediaType, visitor: &mut impl $visitor) {
+                visitor.visit_media_type(content_type, media_type);
+                media_type.schema.walk(visitor);
+                media_type.examples.walk(visitor);
+                if let Some(encoding) = &$($mutability)? media_type.encoding {
+                    for (_, encoding) in encoding {
+                        encoding.headers.walk(visitor);
+                    }
+                }
+            }
+
+            fn walk_path_item(path: &str, item: &$($mutability)? PathItem, visitor: &mut impl $visitor) {
+                visitor.visit_path_item(path, item);
+                item.servers.walk(visitor);
+                item.parameters.walk(visitor);
+                let operations = [
+                    (HttpMethod::Get, &$($mutability)? item.get),
+                    (HttpMethod::Put, &$($mutability)? item.put),
+                    (HttpMethod::Post, &$($mutability)? item.post),
+                    (HttpMethod::Delete, &$($mutability)? item.delete),
+                    (HttpMethod::Options, &$($mutability)? item.options),
+                    (HttpMethod::Head, &$($mutability)? item.head),
+                    (HttpMethod::Patch, &$($mutability)? item.patch),
+                    (HttpMethod::Trace, &$($mutability)? item.trace),
+                ];
+                for (method, operation) in operations {
+                    if let Some(operation) = operation {
+                        walk_operation(path, method, operation, visitor);
+                    }
+                }
+            }
+
+            fn walk_operation(path: &str, method: HttpMethod, operation: &$($mutability)? Operation, visitor: &mut impl $visitor) {
+                visitor.visit_operation(path, method, operation);
+                operation.external_docs.walk(visitor);
+                operation.parameters.walk(visitor);
+                operation.request_body.walk(visitor);
+                operation.responses.default.walk(visitor);
+                operation.responses.data.walk(visitor);
+                operation.callbacks.walk(visitor);
+                operation.servers.walk(visitor);
+            }
+
+            impl Walk for Callback {
+                fn walk(&$($mutability)? self, visitor: &mut impl $visitor) {
+                    for (expression, item) in &$($mutability)? self.data {
+                        walk_path_item(expression, item, visitor);
+                    }
+                }
+            }
+
+            impl Walk for Parameter {
+                fn walk(&$($mutability)? self, visitor: &mut impl $visitor) {
+                    visitor.visit_parameter(self);
+                    self.schema.walk(visitor);
+                    self.examples.walk(visitor);
+                    walk_content(&$($mutability)? self.content, visitor);
+                }
+            }
+
+            impl Walk for Header {
+                fn walk(&$($mutability)? self, visitor: &mut impl $visitor) {
+                    visitor.visit_header(self);
+                    self.schema.walk(visitor);
+                    self.examples.walk(visitor);
+                    walk_content(&$($mutability)? self.content, visitor);
+                }
+            }
+
+            impl Walk for RequestBody {
+                fn walk(&$($mutability)? self, visitor: &mut impl $visitor) {
+                    visitor.visit_request_body(self);
+                    for (content_type, media_type) in &$($mutability)? self.content {
+                        walk_media_type(content_type, media_type, visitor);
+                    }
+                }
+            }
+
+            impl Walk for Response {
+                fn walk(&$($mutability)? self, visitor: &mut impl $visitor) {
+                    visitor.visit_response(self);
+                    self.headers.walk(visitor);
+                    walk_content(&$($mutability)? self.content, visitor);
+                    self.links.walk(visitor);
+                }
+            }
+
+            impl Walk for Link {
+                fn walk(&$($mutability)? self, visitor: &mut impl $visitor) {
+                    visitor.visit_link(self);
+                    self.server.walk(visitor);
+                }
+            }
+
+            impl Walk for Schema {
+                fn walk(&$($mutability)? self, visitor: &mut impl $visitor) {
+                    visitor.visit_schema(self);
+                    self.properties.walk(visitor);
+                    self.items.walk(visitor);
+                    self.external_docs.walk(visitor);
+                    for (keyword, value) in &$($mutability)? self.extras {
+                        if !SUBSCHEMA_KEYWORDS.contains(&keyword.as_str()) {
+                            continue;
+                        }
+                        match value {
+                            Any::Array(values) => {
+                                for value in values {
+                                    walk_untyped_schema(value, visitor);
+                                }
+                            }
+                            Any::Object(_) => walk_untyped_schema(value, visitor),
+                            _ => {}
+                        }
+                    }
+                }
+            }
+
+            /// Walk a schema kept untyped in `extras`, writing it back when walking mutably.
+            #[allow(unused_mut)]
+            fn walk_untyped_schema(value: &$($mutability)? Any, visitor: &mut impl $visitor) {
+                let Ok(mut schema) = serde_json::from_value::<Referenceable<Schema>>(value.clone()) else {
+                    return;
+                };
+                schema.walk(visitor);
+                $(
+                    let _ = stringify!($mutability);
+                    *value = serde_json::to_value(&schema).expect("a schema is valid JSON");
+                )?
+            }
+
+            impl Walk for Components {
+                fn walk(&$($mutability)? self, visitor: &mut impl $visitor) {
+                    self.schemas.walk(visitor);
+                    self.responses.walk(visitor);
+                    self.parameters.walk(visitor);
+                    self.examples.walk(visitor);
+                    self.request_bodies.walk(visitor);
+                    self.headers.walk(visitor);
+                    self.security_schemes.walk(visitor);
+                    self.links.walk(visitor);
+                    self.callbacks.walk(visitor);
+                }
+            }
+
+            impl Walk for Tag {
+                fn walk(&$($mutability)? self, visitor: &mut impl $visitor) {
+                    visitor.visit_tag(self);
+                    self.external_docs.walk(visitor);
+                }
+            }
+
+            impl Walk for Server {
+                fn walk(&$($mutability)? self, visitor: &mut impl $visitor) {
+                    visitor.visit_server(self);
+                }
+            }
+
+            impl Walk for Example {
+                fn walk(&$($mutability)? self, visitor: &mut impl $visitor) {
+                    visitor.visit_example(self);
+                }
+            }
+
+            impl Walk for SecurityScheme {
+                fn walk(&$($mutability)? self, visitor: &mut impl $visitor) {
+                    visitor.visit_security_scheme(self);
+                }
+            }
+
+            impl Walk for ExternalDocumentation {
+                fn walk(&$($mutability)? self, visitor: &mut impl $visitor) {
+                    visitor.visit_external_documentation(self);
+                }
+            }
+
+            visitor.visit_info(&$($mutability)? spec.info);
+            spec.servers.walk(visitor);
+            for (path, item) in &$($mutability)? spec.paths {
+                walk_path_item(path, item, visitor);
+            }
+            spec.components.walk(visitor);
+            spec.tags.walk(visitor);
+            spec.external_docs.walk(visitor);
+        }
+    };
+}
+
+visitor!(
+    /// Callbacks for a read-only walk with [`walk`]. Every method does nothing by default.
+    Visitor, walk,
+);
+visitor!(
+    /// Callbacks for a walk with [`walk_mut`] that may change the document. Every method does nothing by default.
+    VisitorMut, walk_mut, mut
+);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Default)]
+    struct Counter {
+        schemas: usize,
+        references: Vec<String>,
+        operations: Vec<(String, HttpMethod)>,
+    }
+
+    impl Visitor for Counter {
+        fn visit_operation(&mut self, path: &str, method: HttpMethod, _operation: &Operation) {
+            self.operations.push((path.to_string(), method));
+        }
+        fn visit_schema(&mut self, _schema: &Schema) {
+            self.schemas += 1;
+        }
+        fn visit_reference(&mut self, kind: &str, reference: &Reference) {
+            assert_eq!(kind, "schemas");
+            self.references.push(reference._ref.clone());
+        }
+    }
+
+    #[test]
+    fn should_count_schemas() {
+        let spec: OpenAPIV3 =
+            serde_json::from_str(include_str!("../examples/v3.0/json/petstore.json")).unwrap();
+        let mut counter = Counter::default();
+        walk(&spec, &mut counter);
+        // 8 component schemas and properties, 2 inline parameter schemas, 1 header schema
+        assert_eq!(counter.schemas, 11);
+        assert_eq!(counter.references.len(), 6);
+        assert_eq!(counter.operations.len(), 3);
+    }
+
+    #[test]
+    fn should_change_document_in_place() {
+        struct Deprecate;
+        impl VisitorMut for Deprecate {
+            fn visit_operation(&mut self, _: &str, _: HttpMethod, operation: &mut Operation) {
+                operation.deprecated = Some(true);
+            }
+        }
+        let mut spec: OpenAPIV3 =
+            serde_json::from_str(include_str!("../examples/v3.0/json/petstore.json")).unwrap();
+        walk_mut(&mut spec, &mut Deprecate);
+        assert_eq!(spec.deprecated_operations().len(), 3);
+    }
+
+    #[test]
+    fn should_walk_composed_schemas() {
+        let mut spec: OpenAPIV3 = serde_json::from_value(serde_json::json!({
+            "openapi": "3.0.3",
+            "info": {"title": "composed", "version": "1.0"},
+            "paths": {},
+            "components": {"schemas": {
+                "Pet": {"type": "object"},
+                "Cat": {"allOf": [{"$ref": "#/components/schemas/Pet"}, {"type": "object", "properties": {"lives": {"type": "integer"}}}]},
+                "Tags": {"type": "object", "additionalProperties": {"type": "string"}, "not": {"type": "array"}}
+            }}
+        }))
+        .unwrap();
+        let mut counter = Counter::default();
+        walk(&spec, &mut counter);
+        assert_eq!(counter.references, vec!["#/components/schemas/Pet"]);
+        // the 3 components, the second allOf element and its property, additionalProperties and not
+        assert_eq!(counter.schemas, 7);
+
+        struct Describe;
+        impl VisitorMut for Describe {
+            fn visit_schema(&mut self, schema: &mut Schema) {
+                schema.description = Some("described".to_string());
+            }
+        }
+        walk_mut(&mut spec, &mut Describe);
+        let cat = serde_json::to_value(&spec.components.unwrap().schemas.unwrap()["Cat"]).unwrap();
+        assert_eq!(cat["allOf"][1]["description"], "described");
+        assert_eq!(
+            cat["allOf"][1]["properties"]["lives"]["description"],
+            "described"
+        );
+    }
+}